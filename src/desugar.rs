@@ -1,34 +1,87 @@
+use std::fmt;
+
+use serde::Serialize;
+
 use crate::tokenizer::AssignOp;
 use crate::{
-    parser::{Builtin, SugaredExpr, SugaredStatement},
-    tokenizer::Operator,
+    parser::{Builtin, SugaredExpr, SugaredParam, SugaredStatement},
+    tokenizer::{Operator, Position},
 };
 
-#[derive(Debug, PartialEq, Clone)]
+/// The name that the value of a switch statement's expression is bound to. It is not a valid
+/// identifier, so it cannot shadow or be shadowed by a variable in the program.
+const SWITCH_VAR_NAME: &str = "#switch";
+
+/// The name that the list of a destructuring `let` is bound to, for the same reason as
+/// [SWITCH_VAR_NAME].
+const DESTRUCTURE_VAR_NAME: &str = "#destructure";
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct Procedure {
     pub name: String,
-    pub params: Vec<String>,
+    pub params: Vec<Param>,
     pub body: Statement,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// A parameter of a procedure or lambda, with an optional default value which is evaluated when
+/// the argument for the parameter is omitted. A variadic parameter is bound to a list of every
+/// argument after the other parameters.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Param {
+    pub name: String,
+    pub default: Option<Expr>,
+    pub variadic: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum Statement {
-    Expr(Expr),
-    Let(String, Expr),
-    Const(String, Expr),
-    Assign(String, Expr),
-    If(Expr, Box<Statement>, Option<Box<Statement>>),
-    While(Expr, Box<Statement>),
+    Expr(Expr, Position),
+    Let(String, Expr, Position),
+    Const(String, Expr, Position),
+    Assign(String, Expr, Position),
+    /// An assignment to an element of the list stored in a variable, such as `a[0][1] = v`. The
+    /// index expressions are ordered from the outermost list to the innermost list.
+    IndexAssign(String, Vec<Expr>, Expr, Position),
+    If(Expr, Box<Statement>, Option<Box<Statement>>, Position),
+    /// A while loop with a condition, a body, an optional update statement which is run after
+    /// every iteration of the body (including iterations that end with a `continue`), and an
+    /// optional else block which is run when the condition becomes false, but not after a `break`.
+    While(
+        Expr,
+        Box<Statement>,
+        Option<Box<Statement>>,
+        Option<Box<Statement>>,
+        Position,
+    ),
     Block(Vec<Statement>),
-    Return(Option<Expr>),
+    Return(Option<Expr>, Position),
     Break,
     Continue,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+impl Statement {
+    /// Returns the position of the start of this statement in the source program, or `None` if
+    /// this statement does not evaluate any expressions of its own.
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            Statement::Expr(.., position)
+            | Statement::Let(.., position)
+            | Statement::Const(.., position)
+            | Statement::Assign(.., position)
+            | Statement::IndexAssign(.., position)
+            | Statement::If(.., position)
+            | Statement::While(.., position)
+            | Statement::Return(.., position) => Some(*position),
+            Statement::Block(_) | Statement::Break | Statement::Continue => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum Expr {
     Nil,
-    Num(f64),
+    Num(i64),
+    Float(f64),
     Bool(bool),
     Str(String),
     Var(String),
@@ -36,114 +89,235 @@ pub enum Expr {
     Unary(Operator, Box<Expr>),
     PrimitiveCall(Builtin, Vec<Expr>),
     Call(Box<Expr>, Vec<Expr>),
-    Lambda(Vec<String>, Box<Statement>),
+    Lambda(Vec<Param>, Box<Statement>),
     Index(Box<Expr>, Box<Expr>),
+    /// A chain of two or more relational comparisons. The operands are evaluated from left to
+    /// right, and evaluation stops at the first comparison which is false.
+    Comparison(Box<Expr>, Vec<(Operator, Expr)>),
+    /// A sequence of expressions which are evaluated in order. The value of the sequence is the
+    /// value of its last expression, so a sequence is never empty.
+    Sequence(Vec<Expr>),
+    /// A block of statements which is evaluated in a new scope. Its value is the value of its
+    /// last statement, and a `return`, `break`, or `continue` in it leaves the statement that
+    /// contains the block.
+    Block(Vec<Statement>),
+    /// An argument of a call which is expanded into one argument for each element of the list
+    /// that its expression evaluates to.
+    Spread(Box<Expr>),
 }
 
 fn desugar_statements(sugared_statements: Vec<SugaredStatement>) -> Vec<Statement> {
     sugared_statements
-        .iter()
-        .map(|s| desugar_statement(s.clone()))
+        .into_iter()
+        .flat_map(desugar_statement_into_list)
+        .collect()
+}
+
+/// Desugars a statement into the statements which replace it in the enclosing block. This is a
+/// single statement for everything but a destructuring `let`, whose variables must be declared
+/// in the enclosing block rather than a nested one.
+pub fn desugar_statement_into_list(sugared_statement: SugaredStatement) -> Vec<Statement> {
+    match sugared_statement {
+        SugaredStatement::DestructuringLet(var_names, sugared_list_expr, position) => {
+            let list_var = Expr::Var(DESTRUCTURE_VAR_NAME.to_string());
+            // the list is bound with `let` so that a block may contain several destructurings
+            let mut statements = vec![
+                Statement::Let(
+                    DESTRUCTURE_VAR_NAME.to_string(),
+                    desugar_expression(sugared_list_expr),
+                    position,
+                ),
+                Statement::Expr(
+                    Expr::PrimitiveCall(
+                        Builtin::DestructureLength,
+                        vec![list_var.clone(), Expr::Num(var_names.len() as i64)],
+                    ),
+                    position,
+                ),
+            ];
+            for (index, var_name) in var_names.into_iter().enumerate() {
+                let element_expr = Expr::Index(
+                    Box::new(list_var.clone()),
+                    Box::new(Expr::Num(index as i64)),
+                );
+                statements.push(Statement::Let(var_name, element_expr, position));
+            }
+            statements
+        }
+        sugared_statement => vec![desugar_statement(sugared_statement)],
+    }
+}
+
+pub fn desugar_params(sugared_params: Vec<SugaredParam>) -> Vec<Param> {
+    sugared_params
+        .into_iter()
+        .map(
+            |SugaredParam {
+                 name,
+                 default,
+                 variadic,
+             }| Param {
+                name,
+                default: default.map(desugar_expression),
+                variadic,
+            },
+        )
         .collect()
 }
 
 pub fn desugar_statement(sugared_statement: SugaredStatement) -> Statement {
     match sugared_statement {
-        SugaredStatement::Expr(sugared_expr) => Statement::Expr(desugar_expression(sugared_expr)),
-        SugaredStatement::Let(name, sugared_expr) => {
-            Statement::Let(name, desugar_expression(sugared_expr))
+        SugaredStatement::Expr(sugared_expr, position) => {
+            Statement::Expr(desugar_expression(sugared_expr), position)
         }
-        SugaredStatement::Const(name, sugared_expr) => {
-            Statement::Const(name, desugar_expression(sugared_expr))
+        SugaredStatement::Let(name, sugared_expr, position) => {
+            Statement::Let(name, desugar_expression(sugared_expr), position)
         }
-        SugaredStatement::Assign(name, sugared_expr) => {
-            Statement::Assign(name, desugar_expression(sugared_expr))
+        SugaredStatement::Const(name, sugared_expr, position) => {
+            Statement::Const(name, desugar_expression(sugared_expr), position)
         }
-        SugaredStatement::If(if_cond, then_block, else_ifs, else_option) => {
-            let desugared_else_option = match else_option {
-                Some(else_block) => Some(desugar_statement(*else_block)),
-                None => None,
-            };
+        sugared_statement @ SugaredStatement::DestructuringLet(..) => {
+            Statement::Block(desugar_statement_into_list(sugared_statement))
+        }
+        SugaredStatement::Assign(name, sugared_expr, position) => {
+            Statement::Assign(name, desugar_expression(sugared_expr), position)
+        }
+        SugaredStatement::IndexAssign(name, sugared_index_exprs, sugared_expr, position) => {
+            Statement::IndexAssign(
+                name,
+                sugared_index_exprs
+                    .into_iter()
+                    .map(desugar_expression)
+                    .collect(),
+                desugar_expression(sugared_expr),
+                position,
+            )
+        }
+        SugaredStatement::If(if_cond, then_block, else_ifs, else_option, position) => {
+            let desugared_else_option =
+                else_option.map(|else_block| desugar_statement(*else_block));
 
+            // each else-if branch keeps its own position, so that an error in its condition points
+            // at the branch rather than at the start of the whole chain
             let nested_else_ifs = else_ifs.into_iter().rfold(
                 desugared_else_option,
-                |acc, (cur_sugared_cond_expr, cur_sugared_block)| {
-                    return Some(Statement::If(
+                |acc, (cur_sugared_cond_expr, cur_sugared_block, cur_position)| {
+                    Some(Statement::If(
                         desugar_expression(cur_sugared_cond_expr),
                         Box::new(desugar_statement(cur_sugared_block)),
-                        match acc {
-                            Some(acc) => Some(Box::new(acc)),
-                            None => None,
-                        },
-                    ));
+                        acc.map(Box::new),
+                        cur_position,
+                    ))
                 },
             );
 
-            let nested_else_ifs = match nested_else_ifs {
-                Some(statement) => Some(Box::new(statement)),
-                None => None,
-            };
+            let nested_else_ifs = nested_else_ifs.map(Box::new);
 
-            return Statement::If(
+            Statement::If(
                 desugar_expression(if_cond),
                 Box::new(desugar_statement(*then_block)),
                 nested_else_ifs,
-            );
+                position,
+            )
         }
 
-        SugaredStatement::Return(sugared_expr_option) => {
-            Statement::Return(match sugared_expr_option {
-                Some(sugared_expr) => Some(desugar_expression(sugared_expr)),
-                None => None,
-            })
+        SugaredStatement::Return(sugared_expr_option, position) => {
+            Statement::Return(sugared_expr_option.map(desugar_expression), position)
         }
-        SugaredStatement::While(sugared_while_cond, sugared_while_body) => Statement::While(
+        SugaredStatement::While(
+            sugared_while_cond,
+            sugared_while_body,
+            sugared_else_option,
+            position,
+        ) => Statement::While(
             desugar_expression(sugared_while_cond),
             Box::new(desugar_statement(*sugared_while_body)),
+            None,
+            sugared_else_option.map(|else_block| Box::new(desugar_statement(*else_block))),
+            position,
         ),
         SugaredStatement::For(
             sugared_var_statement,
             sugared_stop_cond,
             sugared_reassign_statement,
             sugared_for_block_statements,
+            sugared_else_option,
+            position,
         ) => {
             let desugared_var_statement = desugar_statement(*sugared_var_statement);
             let desugared_stop_cond = desugar_expression(sugared_stop_cond);
             let desugared_reassign_statement = desugar_statement(*sugared_reassign_statement);
-            let mut while_block_statements = desugar_statements(sugared_for_block_statements);
-
-            while_block_statements.append(&mut vec![desugared_reassign_statement]);
+            let while_block_statements = desugar_statements(sugared_for_block_statements);
 
             let while_statement = Statement::While(
                 desugared_stop_cond,
                 Box::new(Statement::Block(while_block_statements)),
+                Some(Box::new(desugared_reassign_statement)),
+                sugared_else_option.map(|else_block| Box::new(desugar_statement(*else_block))),
+                position,
+            );
+
+            Statement::Block(vec![desugared_var_statement, while_statement])
+        }
+        SugaredStatement::Switch(
+            sugared_switch_expr,
+            sugared_cases,
+            sugared_default_option,
+            position,
+        ) => {
+            // the switch expression is evaluated once and bound to a name which cannot collide
+            // with an identifier, then compared against each case value in order
+            let switch_var = Expr::Var(SWITCH_VAR_NAME.to_string());
+            let if_chain_option = sugared_cases.into_iter().rfold(
+                sugared_default_option.map(|default_block| desugar_statement(*default_block)),
+                |acc, (sugared_case_expr, sugared_case_block)| {
+                    Some(Statement::If(
+                        Expr::Binary(
+                            Operator::Eq,
+                            Box::new(switch_var.clone()),
+                            Box::new(desugar_expression(sugared_case_expr)),
+                        ),
+                        Box::new(desugar_statement(sugared_case_block)),
+                        acc.map(Box::new),
+                        position,
+                    ))
+                },
             );
 
-            return Statement::Block(vec![desugared_var_statement, while_statement]);
+            let mut statements = vec![Statement::Const(
+                SWITCH_VAR_NAME.to_string(),
+                desugar_expression(sugared_switch_expr),
+                position,
+            )];
+            statements.extend(if_chain_option);
+            Statement::Block(statements)
         }
         SugaredStatement::Break => Statement::Break,
         SugaredStatement::Continue => Statement::Continue,
         SugaredStatement::Block(sugared_statements) => {
             Statement::Block(desugar_statements(sugared_statements))
         }
-        SugaredStatement::OperatorAssignment(assign_op, id, expr) => match assign_op {
-            AssignOp::Plus => Statement::Assign(
-                id.to_string(),
-                Expr::Binary(
-                    Operator::Plus,
-                    Box::new(Expr::Var(id)),
-                    Box::new(desugar_expression(expr)),
-                ),
-            ),
-            AssignOp::Minus => Statement::Assign(
+        SugaredStatement::OperatorAssignment(assign_op, id, expr, position) => {
+            let op = match assign_op {
+                AssignOp::Plus => Operator::Plus,
+                AssignOp::Minus => Operator::Minus,
+                AssignOp::Times => Operator::Times,
+                AssignOp::Div => Operator::Div,
+                AssignOp::Mod => Operator::Mod,
+                AssignOp::Pow => Operator::Pow,
+                AssignOp::LogicAnd => Operator::LogicAnd,
+                AssignOp::LogicOr => Operator::LogicOr,
+            };
+            Statement::Assign(
                 id.to_string(),
                 Expr::Binary(
-                    Operator::Minus,
+                    op,
                     Box::new(Expr::Var(id)),
                     Box::new(desugar_expression(expr)),
                 ),
-            ),
-        },
+                position,
+            )
+        }
     }
 }
 
@@ -151,6 +325,7 @@ fn desugar_expression(sugared_expr: SugaredExpr) -> Expr {
     match sugared_expr {
         SugaredExpr::Nil => Expr::Nil,
         SugaredExpr::Num(n) => Expr::Num(n),
+        SugaredExpr::Float(n) => Expr::Float(n),
         SugaredExpr::Bool(b) => Expr::Bool(b),
         SugaredExpr::Str(s) => Expr::Str(s),
         SugaredExpr::Var(id) => Expr::Var(id),
@@ -174,12 +349,273 @@ fn desugar_expression(sugared_expr: SugaredExpr) -> Expr {
                 .map(|sugared_arg_expr| desugar_expression(sugared_arg_expr.clone()))
                 .collect(),
         ),
-        SugaredExpr::Lambda(params, sugared_body) => {
-            Expr::Lambda(params, Box::new(desugar_statement(*sugared_body)))
-        }
+        SugaredExpr::Lambda(params, sugared_body) => Expr::Lambda(
+            desugar_params(params),
+            Box::new(desugar_statement(*sugared_body)),
+        ),
         SugaredExpr::Index(sugared_indexable_expr, sugared_index_expr) => Expr::Index(
             Box::new(desugar_expression(*sugared_indexable_expr)),
             Box::new(desugar_expression(*sugared_index_expr)),
         ),
+        SugaredExpr::List(sugared_elements) => Expr::PrimitiveCall(
+            Builtin::List,
+            sugared_elements
+                .into_iter()
+                .map(desugar_expression)
+                .collect(),
+        ),
+        SugaredExpr::Comparison(sugared_first, sugared_comparisons) => Expr::Comparison(
+            Box::new(desugar_expression(*sugared_first)),
+            sugared_comparisons
+                .into_iter()
+                .map(|(op, sugared_expr)| (op, desugar_expression(sugared_expr)))
+                .collect(),
+        ),
+        SugaredExpr::Sequence(sugared_exprs) => {
+            Expr::Sequence(sugared_exprs.into_iter().map(desugar_expression).collect())
+        }
+        SugaredExpr::Block(sugared_statements) => {
+            Expr::Block(desugar_statements(sugared_statements))
+        }
+        SugaredExpr::Spread(sugared_expr) => {
+            Expr::Spread(Box::new(desugar_expression(*sugared_expr)))
+        }
+    }
+}
+
+/// The number of spaces that each nested block is indented by when displaying statements.
+const INDENT_WIDTH: usize = 2;
+
+impl fmt::Display for Procedure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "proc {}", self.name)?;
+        write_params(f, &self.params, 0)?;
+        write!(f, " ")?;
+        write_statement(f, &self.body, 0)
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_statement(f, self, 0)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_expr(f, self, 0)
+    }
+}
+
+/// Writes `statement` as Linger source code, where `indent` is the indentation of the line that
+/// the statement begins on. A while loop's update statement is written at the end of the loop's
+/// body.
+fn write_statement(
+    f: &mut fmt::Formatter<'_>,
+    statement: &Statement,
+    indent: usize,
+) -> fmt::Result {
+    match statement {
+        Statement::Expr(expr, _) => {
+            write_expr(f, expr, indent)?;
+            write!(f, ";")
+        }
+        Statement::Let(id, expr, _) => {
+            write!(f, "let {id} = ")?;
+            write_expr(f, expr, indent)?;
+            write!(f, ";")
+        }
+        Statement::Const(id, expr, _) => {
+            write!(f, "const {id} = ")?;
+            write_expr(f, expr, indent)?;
+            write!(f, ";")
+        }
+        Statement::Assign(id, expr, _) => {
+            write!(f, "{id} = ")?;
+            write_expr(f, expr, indent)?;
+            write!(f, ";")
+        }
+        Statement::IndexAssign(id, index_exprs, expr, _) => {
+            write!(f, "{id}")?;
+            for index_expr in index_exprs {
+                write!(f, "[")?;
+                write_expr(f, index_expr, indent)?;
+                write!(f, "]")?;
+            }
+            write!(f, " = ")?;
+            write_expr(f, expr, indent)?;
+            write!(f, ";")
+        }
+        Statement::If(cond_expr, then_statement, else_statement_option, _) => {
+            write!(f, "if (")?;
+            write_expr(f, cond_expr, indent)?;
+            write!(f, ") ")?;
+            write_statement(f, then_statement, indent)?;
+            match else_statement_option {
+                Some(else_statement) => {
+                    write!(f, " else ")?;
+                    write_statement(f, else_statement, indent)
+                }
+                None => Ok(()),
+            }
+        }
+        Statement::While(cond_expr, body, update_statement_option, else_statement_option, _) => {
+            write!(f, "while (")?;
+            write_expr(f, cond_expr, indent)?;
+            write!(f, ") ")?;
+            match update_statement_option {
+                Some(update_statement) => {
+                    let inner_indent = indent + INDENT_WIDTH;
+                    writeln!(f, "{{")?;
+                    write!(f, "{:inner_indent$}", "")?;
+                    write_statement(f, body, inner_indent)?;
+                    writeln!(f)?;
+                    write!(f, "{:inner_indent$}", "")?;
+                    write_statement(f, update_statement, inner_indent)?;
+                    writeln!(f)?;
+                    write!(f, "{:indent$}}}", "")?;
+                }
+                None => write_statement(f, body, indent)?,
+            }
+            match else_statement_option {
+                Some(else_statement) => {
+                    write!(f, " else ")?;
+                    write_statement(f, else_statement, indent)
+                }
+                None => Ok(()),
+            }
+        }
+        Statement::Block(statements) => write_block(f, statements, indent),
+        Statement::Return(Some(expr), _) => {
+            write!(f, "return ")?;
+            write_expr(f, expr, indent)?;
+            write!(f, ";")
+        }
+        Statement::Return(None, _) => write!(f, "return;"),
+        Statement::Break => write!(f, "break;"),
+        Statement::Continue => write!(f, "continue;"),
+    }
+}
+
+/// Writes `statements` as a bracketed block, where `indent` is the indentation of the line that the
+/// block begins on.
+fn write_block(f: &mut fmt::Formatter<'_>, statements: &[Statement], indent: usize) -> fmt::Result {
+    let inner_indent = indent + INDENT_WIDTH;
+    writeln!(f, "{{")?;
+    for statement in statements {
+        write!(f, "{:inner_indent$}", "")?;
+        write_statement(f, statement, inner_indent)?;
+        writeln!(f)?;
+    }
+    write!(f, "{:indent$}}}", "")
+}
+
+/// Writes `expr` as Linger source code, where `indent` is the indentation of the line that the
+/// expression is on. Binary and negation expressions are parenthesized so that the written code
+/// does not depend on operator precedence.
+fn write_expr(f: &mut fmt::Formatter<'_>, expr: &Expr, indent: usize) -> fmt::Result {
+    match expr {
+        Expr::Nil => write!(f, "nil"),
+        Expr::Num(n) => write!(f, "{n}"),
+        Expr::Float(n) => write!(f, "{n:?}"),
+        Expr::Bool(b) => write!(f, "{b}"),
+        Expr::Str(s) => write!(f, "{s:?}"),
+        Expr::Var(id) => write!(f, "{id}"),
+        Expr::Binary(op, left, right) => {
+            write!(f, "(")?;
+            write_expr(f, left, indent)?;
+            write!(f, " {op} ")?;
+            write_expr(f, right, indent)?;
+            write!(f, ")")
+        }
+        Expr::Unary(op @ (Operator::PostIncrement | Operator::PostDecrement), operand) => {
+            write_expr(f, operand, indent)?;
+            write!(f, "{op}")
+        }
+        Expr::Unary(op @ (Operator::PreIncrement | Operator::PreDecrement), operand) => {
+            write!(f, "{op}")?;
+            write_expr(f, operand, indent)
+        }
+        Expr::Unary(op, operand) => {
+            write!(f, "({op}")?;
+            write_expr(f, operand, indent)?;
+            write!(f, ")")
+        }
+        Expr::PrimitiveCall(builtin, args) => {
+            write!(f, "{builtin}")?;
+            write_args(f, args, indent)
+        }
+        Expr::Call(proc_expr, args) => {
+            write_expr(f, proc_expr, indent)?;
+            write_args(f, args, indent)
+        }
+        Expr::Lambda(params, body) => {
+            write!(f, "(")?;
+            write_params(f, params, indent)?;
+            write!(f, " -> ")?;
+            write_statement(f, body, indent)?;
+            write!(f, ")")
+        }
+        Expr::Index(list_expr, index_expr) => {
+            write_expr(f, list_expr, indent)?;
+            write!(f, "[")?;
+            write_expr(f, index_expr, indent)?;
+            write!(f, "]")
+        }
+        Expr::Comparison(first, comparisons) => {
+            write!(f, "(")?;
+            write_expr(f, first, indent)?;
+            for (op, expr) in comparisons {
+                write!(f, " {op} ")?;
+                write_expr(f, expr, indent)?;
+            }
+            write!(f, ")")
+        }
+        Expr::Sequence(exprs) => write_args(f, exprs, indent),
+        // a block is parenthesized so that it is not read as a block statement
+        Expr::Block(statements) => {
+            write!(f, "(")?;
+            write_block(f, statements, indent)?;
+            write!(f, ")")
+        }
+        Expr::Spread(expr) => {
+            write!(f, "...")?;
+            write_expr(f, expr, indent)
+        }
+    }
+}
+
+/// Writes `params` as a parenthesized, comma-separated list of parameters and their defaults.
+pub(crate) fn write_params(
+    f: &mut fmt::Formatter<'_>,
+    params: &[Param],
+    indent: usize,
+) -> fmt::Result {
+    write!(f, "(")?;
+    for (i, param) in params.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        if param.variadic {
+            write!(f, "...")?;
+        }
+        write!(f, "{}", param.name)?;
+        if let Some(default_expr) = &param.default {
+            write!(f, " = ")?;
+            write_expr(f, default_expr, indent)?;
+        }
+    }
+    write!(f, ")")
+}
+
+/// Writes `args` as a parenthesized, comma-separated list of arguments.
+fn write_args(f: &mut fmt::Formatter<'_>, args: &[Expr], indent: usize) -> fmt::Result {
+    write!(f, "(")?;
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write_expr(f, arg, indent)?;
     }
+    write!(f, ")")
 }
@@ -1,17 +1,14 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use crate::{
-    desugar::{Procedure, Statement},
+    desugar::{Param, Procedure, Statement},
     error::RuntimeError::{self, *},
     interpreter::Value,
 };
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub enum AssignmentType {
-    Initialized,
-    Reassigned,
-}
-
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Mutability {
     Constant,
@@ -20,17 +17,92 @@ pub enum Mutability {
 
 #[derive(Debug, Clone)]
 struct TopLevelProcedure {
-    params: Vec<String>,
+    params: Vec<Param>,
     body: Statement,
 }
 
-pub type Entry = (Value, AssignmentType, Mutability);
+pub type Entry = (Value, Mutability);
 pub type Binding = (String, Entry);
 
+type Scope = HashMap<Arc<str>, Entry>;
+
+/// Allocations which an environment keeps so that it can reuse them. The cache is not copied
+/// along with an environment, since a copy can always allocate its own.
+#[derive(Debug, Default)]
+struct ScopeCache {
+    /// Empty scopes left by blocks which have ended, so that entering a block does not allocate.
+    scopes: Vec<Scope>,
+    /// Every name declared in the environment, so that declaring a name again does not allocate.
+    names: HashSet<Arc<str>>,
+}
+
+impl Clone for ScopeCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// The maximum number of nested procedure calls allowed when no other limit is configured.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 5000;
+
+/// The number of bits that integer arithmetic is performed with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IntWidth {
+    /// Arithmetic which wraps around at 32 bits.
+    W32,
+    /// Arithmetic which returns an [IntegerOverflow] error if it overflows 64 bits.
+    #[default]
+    W64,
+}
+
+impl IntWidth {
+    /// Truncates `n` to this width. Truncating a wrapped 64-bit result to 32 bits gives the same
+    /// result as wrapping 32-bit arithmetic.
+    pub fn wrap(self, n: i64) -> i64 {
+        match self {
+            IntWidth::W32 => n as i32 as i64,
+            IntWidth::W64 => n,
+        }
+    }
+
+    /// Truncates every integer in `value`, including the integers in its lists and maps, to this
+    /// width.
+    pub fn wrap_value(self, value: Value) -> Value {
+        match (self, value) {
+            (IntWidth::W64, value) => value,
+            (IntWidth::W32, Value::Num(n)) => Value::Num(self.wrap(n)),
+            (IntWidth::W32, Value::List(list)) => {
+                Value::List(list.into_iter().map(|v| self.wrap_value(v)).collect())
+            }
+            (IntWidth::W32, Value::Map(entries)) => Value::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, v)| (self.wrap_value(key), self.wrap_value(v)))
+                    .collect(),
+            ),
+            (IntWidth::W32, value) => value,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Environment {
-    top_level_procedures: HashMap<String, TopLevelProcedure>,
-    values: HashMap<String, Entry>,
+    /// The top-level procedures, which are shared with the environment of every top-level
+    /// procedure.
+    top_level_procedures: Arc<HashMap<String, TopLevelProcedure>>,
+    /// The values declared in each scope, from the outermost scope to the current one. Looking
+    /// up a name searches from the current scope outward, so a value shadows any value with the
+    /// same name in an outer scope.
+    scopes: Vec<Scope>,
+    cache: ScopeCache,
+    /// The constants which are visible in every scope and procedure, unless they are shadowed.
+    globals: Arc<HashMap<String, Value>>,
+    /// The number of procedure calls that are currently being interpreted.
+    call_depth: usize,
+    max_call_depth: usize,
+    /// Whether evaluated statements and procedure calls are traced to stderr.
+    trace: bool,
+    int_width: IntWidth,
 }
 
 impl Environment {
@@ -40,77 +112,216 @@ impl Environment {
             top_level_procedures.insert(name, TopLevelProcedure { params, body });
         }
         Self {
-            values: HashMap::new(),
-            top_level_procedures,
+            top_level_procedures: Arc::new(top_level_procedures),
+            scopes: vec![HashMap::new()],
+            cache: ScopeCache::default(),
+            globals: Arc::new(HashMap::new()),
+            call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            trace: false,
+            int_width: IntWidth::default(),
         }
     }
 
-    pub fn get(&self, key: String) -> Result<Value, RuntimeError> {
-        match self.values.get(&key) {
-            Some((value, ..)) => Ok(value.clone()),
-            None => match self.top_level_procedures.get(&key) {
-                Some(proc) => Ok(Value::Proc(
-                    proc.params.clone(),
-                    proc.body.clone(),
-                    self.clone(),
-                )),
-                None => Err(UnknownVariable(key)),
-            },
+    pub fn set_max_call_depth(&mut self, max_call_depth: usize) {
+        self.max_call_depth = max_call_depth;
+    }
+
+    pub fn set_trace(&mut self, trace: bool) {
+        self.trace = trace;
+    }
+
+    pub fn is_tracing(&self) -> bool {
+        self.trace
+    }
+
+    pub fn set_int_width(&mut self, int_width: IntWidth) {
+        self.int_width = int_width;
+    }
+
+    pub fn int_width(&self) -> IntWidth {
+        self.int_width
+    }
+
+    pub fn call_depth(&self) -> usize {
+        self.call_depth
+    }
+
+    /// Marks this environment, which belongs to a procedure being called from `caller_env`, as
+    /// one call deeper than `caller_env`. Returns a [StackOverflow] error if this exceeds the
+    /// maximum call depth.
+    pub fn enter_call_from(&mut self, caller_env: &Self) -> Result<(), RuntimeError> {
+        if caller_env.call_depth >= caller_env.max_call_depth {
+            return Err(StackOverflow(caller_env.max_call_depth));
+        }
+        self.call_depth = caller_env.call_depth + 1;
+        self.max_call_depth = caller_env.max_call_depth;
+        self.trace = caller_env.trace;
+        self.int_width = caller_env.int_width;
+        Ok(())
+    }
+
+    /// Enters a nested scope. Values declared in the nested scope may shadow any value from an
+    /// outer scope until the nested scope is left with [Environment::pop_scope].
+    pub fn push_scope(&mut self) {
+        let scope = self.cache.scopes.pop().unwrap_or_default();
+        self.scopes.push(scope);
+    }
+
+    /// Leaves the current scope, discarding the values declared in it.
+    pub fn pop_scope(&mut self) {
+        if let Some(mut scope) = self.scopes.pop() {
+            scope.clear();
+            self.cache.scopes.push(scope);
         }
     }
 
-    pub fn extend(mut self, bindings: Vec<Binding>) -> Self {
-        for (var, value) in bindings {
-            self.values.insert(var, value);
+    /// Returns the scope which values are currently declared in.
+    fn current_scope(&mut self) -> &mut Scope {
+        self.scopes
+            .last_mut()
+            .expect("an environment should always have a scope")
+    }
+
+    /// Returns the entry of the innermost value named `key`, if there is one.
+    fn entry(&self, key: &str) -> Option<&Entry> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(key))
+    }
+
+    /// Returns an environment which only contains the top-level procedures and globals of this
+    /// environment. This is the environment captured by a top-level procedure, so that it can call
+    /// any top-level procedure (including itself) but cannot see the local values of its caller.
+    fn top_level_env(&self) -> Self {
+        Self {
+            top_level_procedures: Arc::clone(&self.top_level_procedures),
+            scopes: vec![HashMap::new()],
+            cache: ScopeCache::default(),
+            globals: Arc::clone(&self.globals),
+            call_depth: self.call_depth,
+            max_call_depth: self.max_call_depth,
+            trace: self.trace,
+            int_width: self.int_width,
         }
-        return self;
     }
 
-    pub fn insert_new_mutable_value(&mut self, key: String, value: Value) {
-        self.values.insert(
-            key,
-            (value, AssignmentType::Initialized, Mutability::Mutable),
-        );
+    /// Adds a global constant to this environment, replacing any existing global with the same
+    /// name.
+    pub fn insert_global(&mut self, key: &str, value: Value) {
+        Arc::make_mut(&mut self.globals).insert(key.to_string(), value);
     }
 
-    pub fn insert_new_constant_value(&mut self, key: String, value: Value) {
-        self.values.insert(
-            key,
-            (value, AssignmentType::Initialized, Mutability::Constant),
-        );
+    /// Adds a top-level procedure to this environment, replacing any existing top-level procedure
+    /// with the same name.
+    pub fn insert_top_level_procedure(&mut self, procedure: Procedure) {
+        let Procedure { name, params, body } = procedure;
+        Arc::make_mut(&mut self.top_level_procedures)
+            .insert(name, TopLevelProcedure { params, body });
     }
 
-    pub fn reassign(&mut self, key: String, value: Value) -> Result<(), RuntimeError> {
-        match self.values.get(&key) {
-            Some((_, _, Mutability::Mutable)) => {
-                self.values.insert(
-                    key,
-                    (value, AssignmentType::Reassigned, Mutability::Mutable),
-                );
-                return Ok(());
-            }
-            Some((_, _, Mutability::Constant)) => return Err(ReassignConstant(key)),
-            None => match self.top_level_procedures.get(&key) {
-                Some(_) => return Err(ReassignTopLevelProc(key)),
-                None => return Err(UnknownVariable(key)),
-            },
+    pub fn get(&self, key: &str) -> Result<Value, RuntimeError> {
+        if let Some((value, _)) = self.entry(key) {
+            return Ok(value.clone());
+        }
+        if let Some(value) = self.globals.get(key) {
+            return Ok(value.clone());
+        }
+        match self.top_level_procedures.get(key) {
+            Some(proc) => Ok(Value::Proc(
+                proc.params.clone(),
+                Box::new(proc.body.clone()),
+                Box::new(self.top_level_env()),
+            )),
+            None => Err(UnknownVariable(key.to_string())),
         }
     }
 
-    pub fn bindings(&self) -> Vec<Binding> {
-        return self.values.clone().into_iter().collect();
+    /// Declares each of `bindings` in the current scope.
+    pub fn extend(&mut self, bindings: Vec<Binding>) {
+        for (var, value) in bindings {
+            let name = self.intern(&var);
+            self.current_scope().insert(name, value);
+        }
     }
 
-    pub fn contains_key(&self, key: &String) -> bool {
-        return self.values.contains_key(key);
+    pub fn insert_new_mutable_value(
+        &mut self,
+        key: &str,
+        value: Value,
+    ) -> Result<(), RuntimeError> {
+        self.insert_new_value(key, (value, Mutability::Mutable))
     }
 
-    pub fn update_reassigned_entries(&mut self, other_env: &Self) -> Result<(), RuntimeError> {
-        for (id, (value, assignment_type, _)) in other_env.bindings() {
-            if self.contains_key(&id) && assignment_type == AssignmentType::Reassigned {
-                self.reassign(id, value)?;
+    pub fn insert_new_constant_value(
+        &mut self,
+        key: &str,
+        value: Value,
+    ) -> Result<(), RuntimeError> {
+        self.insert_new_value(key, (value, Mutability::Constant))
+    }
+
+    /// Declares `key` in the current scope with `entry`, shadowing any value with the same name
+    /// from an outer scope.
+    fn insert_new_value(&mut self, key: &str, entry: Entry) -> Result<(), RuntimeError> {
+        self.ensure_not_constant_in_scope(key)?;
+        let name = self.intern(key);
+        self.current_scope().insert(name, entry);
+        Ok(())
+    }
+
+    /// Returns the shared copy of the name `key`, which is only allocated the first time `key` is
+    /// declared in this environment.
+    fn intern(&mut self, key: &str) -> Arc<str> {
+        match self.cache.names.get(key) {
+            Some(name) => Arc::clone(name),
+            None => {
+                let name: Arc<str> = Arc::from(key);
+                self.cache.names.insert(Arc::clone(&name));
+                name
             }
         }
-        Ok(())
+    }
+
+    /// Returns a [RedeclareConstant] error if `key` is a constant declared in the current scope.
+    fn ensure_not_constant_in_scope(&self, key: &str) -> Result<(), RuntimeError> {
+        match self.scopes.last().and_then(|scope| scope.get(key)) {
+            Some((_, Mutability::Constant)) => Err(RedeclareConstant(key.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Replaces the innermost value named `key` with `value`, in whichever scope it was declared.
+    pub fn reassign(&mut self, key: &str, value: Value) -> Result<(), RuntimeError> {
+        let entry_option = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(key));
+        match entry_option {
+            Some((old_value, Mutability::Mutable)) => {
+                *old_value = value;
+                Ok(())
+            }
+            Some((_, Mutability::Constant)) => Err(ReassignConstant(key.to_string())),
+            None if self.globals.contains_key(key) => Err(ReassignConstant(key.to_string())),
+            None => match self.top_level_procedures.get(key) {
+                Some(_) => Err(ReassignTopLevelProc(key.to_string())),
+                None => Err(UnknownVariable(key.to_string())),
+            },
+        }
+    }
+
+    /// Returns an iterator over the names of the values bound in this environment. A name which
+    /// shadows a value from an outer scope is only returned once.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        let mut seen_names = HashSet::new();
+        self.scopes
+            .iter()
+            .flat_map(|scope| scope.keys())
+            .map(|name| name.as_ref())
+            .filter(move |name| seen_names.insert(*name))
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entry(key).is_some()
     }
 }
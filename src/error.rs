@@ -1,8 +1,8 @@
 use std::fmt::{self, Display};
 
 use crate::{
-    interpreter::Value,
-    tokenizer::{Operator, Token, TokenValue},
+    interpreter::{ControlFlow, Value},
+    tokenizer::{Operator, Position, Token, TokenValue},
 };
 
 /// A Tokenizer Error
@@ -16,6 +16,24 @@ pub enum TokenizerError {
     UnterminatedStringLiteral,
     /// This error occurs when the tokenizer reaches an invalid escape sequence.
     InvalidEscapeSequence(char),
+    /// This error occurs when the tokenizer reaches a block comment that is never closed with
+    /// `*/`.
+    UnterminatedComment,
+    /// This error occurs when the tokenizer reaches a unicode escape sequence that is malformed or
+    /// does not represent a valid unicode code point.
+    InvalidUnicodeEscape(String),
+    /// This error occurs when the tokenizer reaches a number literal with no digits or with digits
+    /// that are invalid for its base, such as `0x` or `0b2`.
+    InvalidNumberLiteral(String),
+    /// This error occurs when the tokenizer reaches an integer literal that is too large to be
+    /// represented as a 64-bit integer.
+    IntegerLiteralOverflow(String),
+    /// This error occurs when the tokenizer tokenizes a template literal but never reaches a
+    /// terminating backtick.
+    UnterminatedTemplateLiteral,
+    /// This error occurs when an expression embedded in a template literal with `${` is never
+    /// closed with `}`.
+    UnterminatedTemplateExpression,
 }
 
 /// A Parse Error
@@ -47,6 +65,25 @@ pub enum ParseError {
     /// This error occurs when the parser expects to parse an assignment statement or an
     /// initialization statement but was unsuccessful.
     ExpectedAssignmentOrInitialization,
+    /// This error occurs when a parameter without a default value follows a parameter with a
+    /// default value.
+    RequiredParamAfterDefault(String),
+    /// This error occurs when a spread argument, such as `...list`, is passed to a builtin
+    /// procedure.
+    SpreadIntoBuiltin(String),
+    /// This error occurs when an operator which has no operator assignment, such as `<<`, is
+    /// followed by `=`.
+    UnsupportedAssignOp(Operator),
+    /// This error occurs when a procedure is called by a name which is not a top-level procedure,
+    /// a variable, or a parameter.
+    UndefinedProcedure(String),
+    /// This error occurs when the result of a top-level procedure is used, but its body can finish
+    /// without reaching a `return` statement. It is only reported as a warning.
+    MissingReturn(String),
+    /// This error occurs when a `break` statement is not inside of a loop.
+    BreakNotInLoop,
+    /// This error occurs when a `continue` statement is not inside of a loop.
+    ContinueNotInLoop,
 }
 
 /// A Runtime Error
@@ -61,16 +98,21 @@ pub enum RuntimeError {
     /// This error occurs when the number of arguments passed to a procedure is different from the
     /// number of parameters defined for that procedure.
     ArgMismatch(String, usize, usize),
-    /// This error occurs when a value is expected to be a boolean but is not.
-    ExpectedBool(Value),
     /// This error occurs when a value is expected to be a integer but is not.
     ExpectedInteger(Value),
     /// This error occurs when a value is expected to be a list but is not
     ExpectedList(Value),
+    /// This error occurs when a value is expected to be a map but is not
+    ExpectedMap(Value),
+    /// This error occurs when a map is indexed by a key that it does not contain
+    KeyNotFound(Value),
     /// This error occurs when a binary operator is used as a unary operator.
     BinaryAsUnary(Operator),
     /// This error occurs when a unary operator is used as a binary operator.
     UnaryAsBinary(Operator),
+    /// This error occurs when `nil`, such as the result of a call which has no value, is an
+    /// operand of an arithmetic, relational or bitwise operator.
+    NilOperand(Operator),
     /// This error occurs when a `break` statement occurs outside of a loop.
     BreakNotInLoop,
     /// This error occurs when a `continue` statement occurs outside of a loop.
@@ -79,13 +121,116 @@ pub enum RuntimeError {
     InvalidAssignmentTarget,
     /// This error occurs when attempting to reassign a constant value
     ReassignConstant(String),
+    /// This error occurs when attempting to redeclare a constant in the scope it was declared in
+    RedeclareConstant(String),
     /// This error occurs when attempting to reassign a top-level procedure
     ReassignTopLevelProc(String),
+    /// This error occurs when dividing an integer by zero, or taking an integer modulo zero
+    DivisionByZero,
+    /// This error occurs when the result of integer arithmetic does not fit in a 64-bit integer
+    IntegerOverflow,
+    /// This error occurs when a list is destructured into a pattern with a different number of
+    /// variables than the list has elements
+    DestructureMismatch(usize, usize),
+    /// This error occurs when an integer is shifted by a negative amount or by 64 or more bits
+    InvalidShiftAmount(i64),
+    /// This error occurs when procedure calls are nested deeper than the maximum call depth
+    StackOverflow(usize),
+    /// This error occurs when a string cannot be parsed into a number or a bool. The second field
+    /// describes what the string was parsed as, such as "a number".
+    ParseFailure(String, &'static str),
+    /// This error occurs when the condition passed to `assert` is false
+    AssertionFailed(Option<String>),
+    /// This error occurs when a list is expected to be non-empty but is empty
+    EmptyList,
     /// This error occurs when attempting to index a non-indexable value
     NotIndexable(Value),
     /// This error occurs when trying to index a value and the index is out
     /// of bounds
     IndexOutOfBounds(i64),
+    /// This error occurs when `read_line` cannot read from stdin, or reads a line which is not
+    /// valid UTF-8
+    ReadFailed(String),
+    /// This wraps another runtime error with the position of the statement that caused it
+    At(Box<RuntimeError>, Position),
+    /// This is not a failure: it unwinds the whole program when `exit` is called, after which
+    /// the interpreter returns the exit code as the program's result
+    Exit(i64),
+    /// This is not a failure: it carries a `return`, `break`, or `continue` in a block expression
+    /// out of the expression, to the statement which contains it
+    BlockControlFlow(Value, ControlFlow),
+}
+
+/// An error from any step of running a Linger program: tokenization, parsing, or interpreting.
+#[derive(Debug, Clone)]
+pub enum LingerError {
+    /// This error occurs when the program could not be tokenized.
+    Tokenizer(TokenizerError),
+    /// This error occurs when the program could not be parsed.
+    Parse(ParseError),
+    /// This error occurs when the program fails while being interpreted.
+    Runtime(RuntimeError),
+}
+
+impl From<TokenizerError> for LingerError {
+    fn from(e: TokenizerError) -> Self {
+        LingerError::Tokenizer(e)
+    }
+}
+
+impl From<ParseError> for LingerError {
+    fn from(e: ParseError) -> Self {
+        LingerError::Parse(e)
+    }
+}
+
+impl From<RuntimeError> for LingerError {
+    fn from(e: RuntimeError) -> Self {
+        LingerError::Runtime(e)
+    }
+}
+
+impl RuntimeError {
+    /// Attaches a position to this error, unless it already has one from an inner statement.
+    pub fn at(self, position: Position) -> RuntimeError {
+        match self {
+            RuntimeError::At(..) | RuntimeError::Exit(_) | RuntimeError::BlockControlFlow(..) => {
+                self
+            }
+            error => RuntimeError::At(Box::new(error), position),
+        }
+    }
+}
+
+impl ParseError {
+    /// Returns the position of the token that caused this error, if the error refers to one.
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            ParseError::UnexpectedToken(Token(_, line, col))
+            | ParseError::Expected(_, Token(_, line, col)) => Some((*line, *col)),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the line of `source` at `position`, prefixed by its line number, with a caret on the
+/// line below pointing at the column of the position. This function returns `None` if `source`
+/// has no such line.
+pub fn source_context(source: &str, position: Position) -> Option<String> {
+    let (line_number, col) = position;
+    let line = source.lines().nth(line_number.checked_sub(1)?)?;
+
+    // tabs are kept so that the caret lines up with the source line however tabs are displayed
+    let caret_padding: String = line
+        .chars()
+        .take(col.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let gutter_width = line_number.to_string().len();
+    Some(format!(
+        "{line_number} | {line}\n{:gutter_width$} | {caret_padding}^",
+        ""
+    ))
 }
 
 impl Display for ParseError {
@@ -121,6 +266,26 @@ impl Display for ParseError {
             ParseError::ExpectedAssignmentOrInitialization => {
                 write!(f, "expected an assignment or initialization statement")
             }
+            ParseError::UndefinedProcedure(proc_name) => {
+                write!(f, "call to undefined procedure \"{proc_name}\"")
+            }
+            ParseError::RequiredParamAfterDefault(param) => write!(
+                f,
+                "parameter \"{param}\" without a default value follows a parameter with a default value"
+            ),
+            ParseError::SpreadIntoBuiltin(builtin) => {
+                write!(f, "cannot spread arguments into builtin \"{builtin}\"")
+            }
+            ParseError::UnsupportedAssignOp(op) => {
+                write!(f, "unsupported operator assignment \"{op}=\"")
+            }
+            ParseError::MissingReturn(proc_name) => {
+                write!(f, "procedure \"{proc_name}\" does not return a value on every path")
+            }
+            ParseError::BreakNotInLoop => write!(f, "break statement found outside of a loop"),
+            ParseError::ContinueNotInLoop => {
+                write!(f, "continue statement found outside of a loop")
+            }
         }
     }
 }
@@ -132,9 +297,26 @@ impl Display for TokenizerError {
             TokenizerError::UnterminatedStringLiteral => {
                 write!(f, "unterminated string literal")
             }
+            TokenizerError::UnterminatedTemplateLiteral => {
+                write!(f, "unterminated template literal")
+            }
+            TokenizerError::UnterminatedTemplateExpression => {
+                write!(f, "unterminated expression in template literal")
+            }
             TokenizerError::InvalidEscapeSequence(char) => {
                 write!(f, "invalid escape sequence \"\\{char}\"")
             }
+            TokenizerError::UnterminatedComment => write!(f, "unterminated block comment"),
+            TokenizerError::InvalidUnicodeEscape(digits) => {
+                write!(f, "invalid unicode escape sequence \"\\u{{{digits}}}\"")
+            }
+            TokenizerError::InvalidNumberLiteral(literal) => {
+                write!(f, "invalid number literal \"{literal}\"")
+            }
+            TokenizerError::IntegerLiteralOverflow(literal) => write!(
+                f,
+                "integer literal \"{literal}\" is too large to be represented as a 64-bit integer"
+            ),
         }
     }
 }
@@ -149,9 +331,6 @@ impl Display for RuntimeError {
                 "procedure \"{}\" expected {} args, instead got {}",
                 proc_name, expected, actual
             ),
-            RuntimeError::ExpectedBool(v) => {
-                write!(f, "expected boolean value, instead got {}", v)
-            }
             RuntimeError::BadArgs(args) => {
                 let arg_strings_vec: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
                 let arg_string = arg_strings_vec.join(", ");
@@ -163,6 +342,9 @@ impl Display for RuntimeError {
             RuntimeError::UnaryAsBinary(op) => {
                 write!(f, "unary operator \"{}\" used as binary operator", op)
             }
+            RuntimeError::NilOperand(op) => {
+                write!(f, "nil used as an operand of \"{op}\", which needs a value")
+            }
             RuntimeError::BreakNotInLoop => write!(f, "break statement found outside of a loop"),
             RuntimeError::ContinueNotInLoop => {
                 write!(f, "continue statement found outside of a loop")
@@ -171,9 +353,37 @@ impl Display for RuntimeError {
             RuntimeError::ReassignConstant(var) => {
                 write!(f, "cannot assign to \"{var}\" because it is a constant")
             }
+            RuntimeError::RedeclareConstant(var) => {
+                write!(
+                    f,
+                    "cannot redeclare \"{var}\" because it is a constant in the same scope"
+                )
+            }
             RuntimeError::ReassignTopLevelProc(proc_name) => {
                 write!(f, "cannot assign to top-level procedure \"{proc_name}\"")
             }
+            RuntimeError::DivisionByZero => write!(f, "division by zero"),
+            RuntimeError::IntegerOverflow => write!(f, "integer overflow"),
+            RuntimeError::DestructureMismatch(expected, actual) => write!(
+                f,
+                "cannot destructure a list of {actual} elements into {expected} variables"
+            ),
+            RuntimeError::InvalidShiftAmount(amount) => write!(
+                f,
+                "cannot shift by {amount} bits, the shift amount must be between 0 and 63"
+            ),
+            RuntimeError::StackOverflow(max_call_depth) => write!(
+                f,
+                "stack overflow: exceeded the maximum call depth of {max_call_depth}"
+            ),
+            RuntimeError::ParseFailure(s, target) => {
+                write!(f, "could not parse \"{s}\" as {target}")
+            }
+            RuntimeError::AssertionFailed(message_option) => match message_option {
+                Some(message) => write!(f, "assertion failed: {message}"),
+                None => write!(f, "assertion failed"),
+            },
+            RuntimeError::EmptyList => write!(f, "expected a non-empty list"),
             RuntimeError::NotIndexable(value) => write!(f, "\"{value}\" is not indexable"),
             RuntimeError::ExpectedInteger(value) => write!(
                 f,
@@ -184,6 +394,37 @@ impl Display for RuntimeError {
                 f,
                 "expected a list, instead got {value}, which is not a list"
             ),
+            RuntimeError::ExpectedMap(value) => {
+                write!(f, "expected a map, instead got {value}, which is not a map")
+            }
+            RuntimeError::KeyNotFound(key) => write!(f, "key \"{key}\" is not in the map"),
+            RuntimeError::ReadFailed(message) => {
+                write!(f, "could not read a line from stdin: {message}")
+            }
+            RuntimeError::At(error, (line, col)) => write!(f, "{error} @ ({line}, {col})"),
+            RuntimeError::Exit(code) => write!(f, "exited with code {code}"),
+            RuntimeError::BlockControlFlow(_, control_flow) => {
+                let statement = format!("{control_flow:?}").to_lowercase();
+                write!(f, "{statement} statement escaped from a block expression")
+            }
+        }
+    }
+}
+
+impl Display for LingerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LingerError::Tokenizer(e) => write!(f, "{e}"),
+            LingerError::Parse(e) => write!(f, "{e}"),
+            LingerError::Runtime(e) => write!(f, "{e}"),
         }
     }
 }
+
+impl std::error::Error for TokenizerError {}
+
+impl std::error::Error for ParseError {}
+
+impl std::error::Error for RuntimeError {}
+
+impl std::error::Error for LingerError {}
@@ -0,0 +1,153 @@
+use crate::{
+    desugar::{Expr, Param, Procedure, Statement},
+    parser::Program,
+    tokenizer::Operator,
+};
+
+/// Folds the constant expressions of `program` into literals. Only arithmetic on numeric literals
+/// and concatenation of string literals is folded, and an expression which would fail when
+/// interpreted, such as one that overflows, is left as it is so that it fails at runtime instead.
+pub fn fold_program(program: Program) -> Program {
+    Program {
+        procedures: program
+            .procedures
+            .into_iter()
+            .map(|proc| Procedure {
+                name: proc.name,
+                params: fold_params(proc.params),
+                body: fold_statement(proc.body),
+            })
+            .collect(),
+        main_params: fold_params(program.main_params),
+        main: fold_statement(program.main),
+    }
+}
+
+fn fold_params(params: Vec<Param>) -> Vec<Param> {
+    params
+        .into_iter()
+        .map(|param| Param {
+            default: param.default.map(fold_expr),
+            ..param
+        })
+        .collect()
+}
+
+fn fold_statement(statement: Statement) -> Statement {
+    match statement {
+        Statement::Expr(expr, position) => Statement::Expr(fold_expr(expr), position),
+        Statement::Let(name, expr, position) => Statement::Let(name, fold_expr(expr), position),
+        Statement::Const(name, expr, position) => Statement::Const(name, fold_expr(expr), position),
+        Statement::Assign(name, expr, position) => {
+            Statement::Assign(name, fold_expr(expr), position)
+        }
+        Statement::IndexAssign(name, index_exprs, expr, position) => Statement::IndexAssign(
+            name,
+            index_exprs.into_iter().map(fold_expr).collect(),
+            fold_expr(expr),
+            position,
+        ),
+        Statement::If(cond_expr, then_statement, else_statement_option, position) => Statement::If(
+            fold_expr(cond_expr),
+            Box::new(fold_statement(*then_statement)),
+            else_statement_option.map(|else_statement| Box::new(fold_statement(*else_statement))),
+            position,
+        ),
+        Statement::While(cond_expr, body, update_option, else_option, position) => {
+            Statement::While(
+                fold_expr(cond_expr),
+                Box::new(fold_statement(*body)),
+                update_option.map(|update| Box::new(fold_statement(*update))),
+                else_option.map(|else_block| Box::new(fold_statement(*else_block))),
+                position,
+            )
+        }
+        Statement::Block(statements) => {
+            Statement::Block(statements.into_iter().map(fold_statement).collect())
+        }
+        Statement::Return(expr_option, position) => {
+            Statement::Return(expr_option.map(fold_expr), position)
+        }
+        Statement::Break => Statement::Break,
+        Statement::Continue => Statement::Continue,
+    }
+}
+
+fn fold_exprs(exprs: Vec<Expr>) -> Vec<Expr> {
+    exprs.into_iter().map(fold_expr).collect()
+}
+
+fn fold_expr(expr: Expr) -> Expr {
+    match expr {
+        Expr::Binary(op, left, right) => {
+            let (left, right) = (fold_expr(*left), fold_expr(*right));
+            match fold_binary(op, &left, &right) {
+                Some(folded) => folded,
+                None => Expr::Binary(op, Box::new(left), Box::new(right)),
+            }
+        }
+        Expr::Unary(op, operand) => match (op, fold_expr(*operand)) {
+            (Operator::Minus, Expr::Num(n)) if n.checked_neg().is_some() => Expr::Num(-n),
+            (Operator::Minus, Expr::Float(n)) => Expr::Float(-n),
+            (op, operand) => Expr::Unary(op, Box::new(operand)),
+        },
+        Expr::PrimitiveCall(builtin, args) => Expr::PrimitiveCall(builtin, fold_exprs(args)),
+        Expr::Call(f_expr, args) => Expr::Call(Box::new(fold_expr(*f_expr)), fold_exprs(args)),
+        Expr::Lambda(params, body) => {
+            Expr::Lambda(fold_params(params), Box::new(fold_statement(*body)))
+        }
+        Expr::Index(indexable_expr, index_expr) => Expr::Index(
+            Box::new(fold_expr(*indexable_expr)),
+            Box::new(fold_expr(*index_expr)),
+        ),
+        Expr::Comparison(first, comparisons) => Expr::Comparison(
+            Box::new(fold_expr(*first)),
+            comparisons
+                .into_iter()
+                .map(|(op, expr)| (op, fold_expr(expr)))
+                .collect(),
+        ),
+        Expr::Sequence(exprs) => Expr::Sequence(fold_exprs(exprs)),
+        Expr::Block(statements) => {
+            Expr::Block(statements.into_iter().map(fold_statement).collect())
+        }
+        Expr::Spread(expr) => Expr::Spread(Box::new(fold_expr(*expr))),
+        Expr::Nil | Expr::Num(_) | Expr::Float(_) | Expr::Bool(_) | Expr::Str(_) | Expr::Var(_) => {
+            expr
+        }
+    }
+}
+
+/// Returns the literal that `left op right` evaluates to, if both operands are literals that the
+/// interpreter would combine without an error.
+fn fold_binary(op: Operator, left: &Expr, right: &Expr) -> Option<Expr> {
+    match (op, left, right) {
+        (Operator::Plus, Expr::Num(num_left), Expr::Num(num_right)) => {
+            num_left.checked_add(*num_right).map(Expr::Num)
+        }
+        (Operator::Minus, Expr::Num(num_left), Expr::Num(num_right)) => {
+            num_left.checked_sub(*num_right).map(Expr::Num)
+        }
+        (Operator::Times, Expr::Num(num_left), Expr::Num(num_right)) => {
+            num_left.checked_mul(*num_right).map(Expr::Num)
+        }
+        (Operator::Plus, Expr::Str(str_left), Expr::Str(str_right)) => {
+            Some(Expr::Str(format!("{str_left}{str_right}")))
+        }
+        // an integer combined with a float is promoted to a float, as in the interpreter
+        (Operator::Plus | Operator::Minus | Operator::Times, left, right) => {
+            let (num_left, num_right) = match (left, right) {
+                (Expr::Float(num_left), Expr::Float(num_right)) => (*num_left, *num_right),
+                (Expr::Float(num_left), Expr::Num(num_right)) => (*num_left, *num_right as f64),
+                (Expr::Num(num_left), Expr::Float(num_right)) => (*num_left as f64, *num_right),
+                _ => return None,
+            };
+            Some(Expr::Float(match op {
+                Operator::Plus => num_left + num_right,
+                Operator::Minus => num_left - num_right,
+                _ => num_left * num_right,
+            }))
+        }
+        _ => None,
+    }
+}
@@ -1,16 +1,32 @@
-use std::fmt;
+use std::{fmt, panic, thread};
 
-use crate::{desugar::Statement, environment::Environment, error::RuntimeError, parser::Program, Writer};
+use crate::{
+    desugar::{Param, Statement},
+    environment::{Environment, IntWidth, DEFAULT_MAX_CALL_DEPTH},
+    error::RuntimeError,
+    fold::fold_program,
+    parser::Program,
+    Writer,
+};
 
-use self::statements::interp_statement;
+use self::expressions::apply_proc;
+pub(crate) use self::statements::interp_statement;
+pub use self::statements::ControlFlow;
 
 #[derive(Clone, Debug)]
 pub enum Value {
-    Num(f64),
+    Num(i64),
+    Float(f64),
     Bool(bool),
     Str(String),
-    Proc(Vec<String>, Statement, Environment),
+    /// A single unicode codepoint.
+    Char(char),
+    /// A procedure with its params, body, and the environment it was created in. The body and
+    /// environment are boxed, since they are much larger than any other value.
+    Proc(Vec<Param>, Box<Statement>, Box<Environment>),
     List(Vec<Value>),
+    /// A map from keys to values. The entries are kept in the order their keys were inserted.
+    Map(Vec<(Value, Value)>),
     // ! consider if Nil should be an explicit value or just return an Option<Value> instead where None represents Nil
     Nil,
 }
@@ -19,15 +35,90 @@ impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Value::Num(n) => write!(f, "{}", n),
+            Value::Float(n) => {
+                if n.fract() == 0.0 && n.is_finite() {
+                    write!(f, "{:.1}", n)
+                } else {
+                    write!(f, "{}", n)
+                }
+            }
             Value::Bool(b) => write!(f, "{}", b),
             Value::Nil => write!(f, "nil"),
             Value::Str(s) => write!(f, "{}", s),
+            Value::Char(c) => write!(f, "{}", c),
             Value::Proc(..) => write!(f, "<lambda>"),
             Value::List(list) => {
                 let values_as_strings: Vec<String> = list.iter().map(|v| v.to_string()).collect();
                 let list_string = values_as_strings.join(", ");
                 write!(f, "[{list_string}]")
             }
+            Value::Map(entries) => {
+                let entries_as_strings: Vec<String> = entries
+                    .iter()
+                    .map(|(key, value)| format!("{key}: {value}"))
+                    .collect();
+                let map_string = entries_as_strings.join(", ");
+                write!(f, "{{{map_string}}}")
+            }
+        }
+    }
+}
+
+impl Value {
+    /// Returns the name of the type of this value.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Num(_) => "num",
+            Value::Float(_) => "float",
+            Value::Bool(_) => "bool",
+            Value::Str(_) => "str",
+            Value::Char(_) => "char",
+            Value::Proc(..) => "lambda",
+            Value::List(_) => "list",
+            Value::Map(_) => "map",
+            Value::Nil => "nil",
+        }
+    }
+
+    /// Returns this value written the way it would be in source code, for debugging. Unlike the
+    /// [Display] of a value, strings are quoted and their special characters are escaped, and a
+    /// char or a map is written as the call to `chr` or `map_of` which produces it.
+    ///
+    /// [Display]: fmt::Display
+    pub fn debug_repr(&self) -> String {
+        match self {
+            Value::Str(s) => {
+                let mut repr = String::from('"');
+                for c in s.chars() {
+                    match c {
+                        '\n' => repr.push_str("\\n"),
+                        '\r' => repr.push_str("\\r"),
+                        '\t' => repr.push_str("\\t"),
+                        '\\' => repr.push_str("\\\\"),
+                        '\0' => repr.push_str("\\0"),
+                        '"' => repr.push_str("\\\""),
+                        c if c.is_control() => repr.push_str(&format!("\\u{{{:x}}}", c as u32)),
+                        c => repr.push(c),
+                    }
+                }
+                repr.push('"');
+                repr
+            }
+            Value::Char(c) => format!("chr({})", *c as u32),
+            Value::List(list) => {
+                let values_as_strings: Vec<String> = list.iter().map(Value::debug_repr).collect();
+                format!("[{}]", values_as_strings.join(", "))
+            }
+            Value::Map(entries) => {
+                let entries_as_strings: Vec<String> = entries
+                    .iter()
+                    .flat_map(|(key, value)| [key.debug_repr(), value.debug_repr()])
+                    .collect();
+                format!("map_of({})", entries_as_strings.join(", "))
+            }
+            Value::Num(_) | Value::Float(_) | Value::Bool(_) | Value::Proc(..) | Value::Nil => {
+                self.to_string()
+            }
         }
     }
 }
@@ -36,8 +127,103 @@ mod expressions;
 mod statements;
 mod utils;
 
-pub fn interp_program<'a>(p: Program, writer: &mut Writer) -> Result<Value, RuntimeError> {
-    return match interp_statement(&mut Environment::new(p.procedures), p.main, false, writer)? {
-        (value, _) => Ok(value),
+/// Options which change how a program is interpreted.
+#[derive(Clone, Debug)]
+pub struct InterpOptions {
+    /// The maximum number of nested procedure calls. Calling a procedure any deeper returns a
+    /// [RuntimeError::StackOverflow] error.
+    pub max_call_depth: usize,
+    /// Whether to write each evaluated statement and each procedure call to stderr.
+    pub trace: bool,
+    /// The number of bits that integer arithmetic is performed with.
+    pub int_width: IntWidth,
+    /// The command-line arguments passed to the program, which it can read from the global `args`
+    /// list.
+    pub args: Vec<String>,
+}
+
+impl Default for InterpOptions {
+    fn default() -> Self {
+        Self {
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            trace: false,
+            int_width: IntWidth::default(),
+            args: vec![],
+        }
+    }
+}
+
+pub fn interp_program(p: Program, writer: &mut Writer) -> Result<Value, RuntimeError> {
+    interp_program_with_options(p, InterpOptions::default(), writer)
+}
+
+/// The stack size of the thread which interprets a program. A nested procedure call uses about
+/// ten kilobytes of stack in release builds, and a few times that in debug builds, so the default
+/// stack of a thread is far too small to reach the maximum call depth.
+pub const INTERPRETER_STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// Runs `f` on a thread with a stack of [INTERPRETER_STACK_SIZE], so that deep recursion is a
+/// [RuntimeError::StackOverflow] error rather than overflowing the stack of the calling thread.
+pub(crate) fn on_interpreter_thread<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    thread::scope(|scope| {
+        let interpreter_thread = thread::Builder::new()
+            .stack_size(INTERPRETER_STACK_SIZE)
+            .spawn_scoped(scope, f)
+            .expect("the interpreter thread should be spawned");
+        match interpreter_thread.join() {
+            Ok(result) => result,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    })
+}
+
+/// Interprets `p` on a thread with a stack large enough to reach the maximum call depth.
+pub fn interp_program_with_options(
+    p: Program,
+    options: InterpOptions,
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    on_interpreter_thread(|| interp_program_on_current_thread(p, options, writer))
+}
+
+fn interp_program_on_current_thread(
+    p: Program,
+    options: InterpOptions,
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    // constant folding uses 64-bit arithmetic, so it would not wrap the way 32-bit arithmetic does
+    let p = match options.int_width {
+        IntWidth::W32 => p,
+        IntWidth::W64 => fold_program(p),
     };
+    let mut env = Environment::new(p.procedures);
+    env.set_max_call_depth(options.max_call_depth);
+    env.set_trace(options.trace);
+    env.set_int_width(options.int_width);
+    let args = Value::List(options.args.into_iter().map(Value::Str).collect());
+    env.insert_global("args", args.clone());
+    let result = match p.main_params.is_empty() {
+        true => interp_statement(&mut env, &p.main, false, writer).map(|(value, _)| value),
+        // a main procedure with params is called with the list of command-line arguments, which
+        // checks its params like any other call
+        false => {
+            let main_proc = Value::Proc(p.main_params, Box::new(p.main), Box::new(env.clone()));
+            apply_proc(&mut env, Some("main".to_string()), main_proc, vec![args], writer)
+        }
+    };
+    match result {
+        Err(RuntimeError::Exit(code)) => Ok(Value::Num(code)),
+        result => result,
+    }
+}
+
+/// Applies the procedure `callee` to `args` in `env`, the same way as calling it with those
+/// arguments. This function returns a [RuntimeError::BadArg] error if `callee` is not a procedure.
+pub fn apply(
+    env: &mut Environment,
+    callee: Value,
+    args: Vec<Value>,
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    apply_proc(env, None, callee, args, writer)
 }
@@ -1,373 +1,1147 @@
+use std::{
+    cmp::Ordering,
+    io::{stdin, Write},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
 use crate::{
-    desugar::Expr,
-    environment::{AssignmentType, Binding, Entry, Environment, Mutability},
+    desugar::{Expr, Param, Statement},
+    environment::{Binding, Entry, Environment, Mutability},
     error::RuntimeError::{self, *},
-    tokenizer::Operator, Writer,
+    parser::Builtin,
+    tokenizer::Operator,
+    Writer,
 };
 
 use super::{
-    statements::interp_statement,
-    utils::{ensure_list, ensure_single_arg},
+    statements::{interp_block, interp_statement, ControlFlow},
+    utils::{
+        checked_floor_div, compare_values, default_order, ensure_args, ensure_arity, ensure_float,
+        ensure_integer, ensure_list, ensure_map, ensure_no_args, ensure_shift_amount,
+        ensure_string, float_operands, float_to_integer, integer_bad_arg, integer_result,
+        is_truthy, map_get, map_insert, merge_sort, numeric_bad_arg, trace, values_equal,
+        wrapping_pow,
+    },
     Value,
 };
 
-pub fn interp_expression<'a>(env: &mut Environment, expr: Expr, writer: &mut Writer) -> Result<Value, RuntimeError> {
+pub fn interp_expression(env: &mut Environment, expr: &Expr, writer: &mut Writer) -> Result<Value, RuntimeError> {
+    // this function is entered for every nested expression, so each kind of expression is
+    // interpreted by a function of its own, which keeps the stack frame of this one small
     match expr {
         Expr::Nil => Ok(Value::Nil),
-        Expr::Num(n) => Ok(Value::Num(n)),
-        Expr::Bool(b) => Ok(Value::Bool(b)),
-        Expr::Str(s) => Ok(Value::Str(s)),
-        Expr::Lambda(params, body) => Ok(Value::Proc(params, *body, env.clone())),
-        Expr::Var(id) => match env.get(id.to_string())? {
-            v => Ok(v),
+        Expr::Num(n) => Ok(Value::Num(env.int_width().wrap(*n))),
+        Expr::Float(n) => Ok(Value::Float(*n)),
+        Expr::Bool(b) => Ok(Value::Bool(*b)),
+        Expr::Str(s) => Ok(Value::Str(s.to_string())),
+        Expr::Lambda(params, body) => {
+            Ok(Value::Proc(params.clone(), body.clone(), Box::new(env.clone())))
+        }
+        Expr::Var(id) => env.get(id),
+        Expr::Binary(op, left, right) => interp_binary(env, *op, left, right, writer),
+        Expr::Unary(op, operand) => interp_unary(env, *op, operand, writer),
+        Expr::Call(f_expr, args) => interp_call(env, f_expr, args, writer),
+        Expr::PrimitiveCall(builtin, args) => {
+            // builtins such as `len` and `to_num` can produce integers too wide for 32 bits
+            interp_primitive_call(env, *builtin, args, writer)
+                .map(|value| env.int_width().wrap_value(value))
+        }
+        Expr::Comparison(first, comparisons) => interp_comparison(env, first, comparisons, writer),
+        Expr::Spread(_) => unreachable!("a spread is only parsed as an argument of a call"),
+        Expr::Sequence(exprs) => interp_sequence(env, exprs, writer),
+        Expr::Block(statements) => interp_block_expression(env, statements, writer),
+        Expr::Index(indexable_expr, index_expr) => {
+            interp_index(env, indexable_expr, index_expr, writer)
+        }
+    }
+}
+
+/// Interprets the unary operation `op` applied to `operand`.
+fn interp_unary(
+    env: &mut Environment,
+    op: Operator,
+    operand: &Expr,
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    match op {
+        Operator::PreIncrement
+        | Operator::PostIncrement
+        | Operator::PreDecrement
+        | Operator::PostDecrement => interp_increment(env, op, operand, writer),
+        Operator::Minus => match interp_expression(env, operand, writer)? {
+            Value::Num(n) => integer_result(env, n.checked_neg(), n.wrapping_neg()),
+            Value::Float(n) => Ok(Value::Float(-n)),
+            Value::Nil => Err(NilOperand(op)),
+            v => Err(BadArg(v)),
         },
-        Expr::Binary(op, left, right) => match op {
-            Operator::Plus => {
-                match (
-                    interp_expression(env, *left, writer)?,
-                    interp_expression(env, *right, writer)?,
-                ) {
-                    (Value::Num(num_left), Value::Num(num_right)) => {
-                        Ok(Value::Num(num_left + num_right))
-                    }
-                    (Value::Str(num_left), Value::Str(num_right)) => {
-                        Ok(Value::Str(num_left + num_right.as_str()))
-                    }
-                    (Value::List(mut list_left), Value::List(mut list_right)) => {
-                        list_left.append(&mut list_right);
-                        Ok(Value::List(list_left))
-                    }
-                    (Value::Num(_), v) => Err(BadArg(v)),
-                    (v, _) => Err(BadArg(v)),
+        Operator::LogicNot => {
+            let value = interp_expression(env, operand, writer)?;
+            Ok(Value::Bool(!is_truthy(&value)))
+        }
+        op => Err(BinaryAsUnary(op)),
+    }
+}
+
+/// Interprets a chain of comparisons such as `a < b <= c`, which is true if every comparison in
+/// it is true. Each operand is evaluated at most once, and the chain stops at the first false
+/// comparison.
+fn interp_comparison(
+    env: &mut Environment,
+    first: &Expr,
+    comparisons: &[(Operator, Expr)],
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    let mut left = interp_expression(env, first, writer)?;
+    for (op, expr) in comparisons {
+        let right = interp_expression(env, expr, writer)?;
+        if !compare_values(*op, left, right.clone())? {
+            return Ok(Value::Bool(false));
+        }
+        left = right;
+    }
+    Ok(Value::Bool(true))
+}
+
+/// Interprets each of `exprs` in order. The value of the sequence is the value of its last
+/// expression.
+fn interp_sequence(
+    env: &mut Environment,
+    exprs: &[Expr],
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    let mut value = Value::Nil;
+    for expr in exprs {
+        value = interp_expression(env, expr, writer)?;
+    }
+    Ok(value)
+}
+
+/// Interprets a block expression. A return, break, or continue in the block is a
+/// [BlockControlFlow] error, which is carried out to the enclosing statement.
+fn interp_block_expression(
+    env: &mut Environment,
+    statements: &[Statement],
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    // the block is interpreted as if it were in a loop, so that a break or continue is carried
+    // out to the enclosing statement, which checks whether it is in a loop itself
+    match interp_block(env, statements, true, writer)? {
+        (value, ControlFlow::Normal) => Ok(value),
+        (value, control_flow) => Err(BlockControlFlow(value, control_flow)),
+    }
+}
+
+/// Interprets the element of the list, the char of the string, or the value of the map that
+/// `indexable_expr` evaluates to, at the index or key that `index_expr` evaluates to.
+fn interp_index(
+    env: &mut Environment,
+    indexable_expr: &Expr,
+    index_expr: &Expr,
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    match interp_expression(env, indexable_expr, writer)? {
+        Value::List(list) => match interp_expression(env, index_expr, writer)? {
+            Value::Num(index) => {
+                if index < 0 {
+                    return Err(IndexOutOfBounds(index));
                 }
+
+                let value = match list.into_iter().nth(index as usize) {
+                    Some(v) => v,
+                    None => return Err(IndexOutOfBounds(index)),
+                };
+
+                Ok(value)
             }
-            Operator::Minus => match (
-                interp_expression(env, *left, writer)?,
-                interp_expression(env, *right, writer)?,
-            ) {
-                (Value::Num(num_left), Value::Num(num_right)) => {
-                    Ok(Value::Num(num_left - num_right))
-                }
-                (Value::Num(_), v) => Err(BadArg(v)),
-                (v, _) => Err(BadArg(v)),
-            },
-            Operator::Eq => match (
-                interp_expression(env, *left, writer)?,
-                interp_expression(env, *right, writer)?,
-            ) {
-                (Value::Num(num_left), Value::Num(num_right)) => {
-                    Ok(Value::Bool(num_left == num_right))
-                }
-                (Value::Bool(bool_left), Value::Bool(bool_right)) => {
-                    Ok(Value::Bool(bool_left == bool_right))
-                }
-                (v_left, v_right) => Err(BadArgs(vec![v_left, v_right])),
-            },
-            Operator::Ne => match (
-                interp_expression(env, *left, writer)?,
-                interp_expression(env, *right, writer)?,
-            ) {
-                (Value::Num(num_left), Value::Num(num_right)) => {
-                    Ok(Value::Bool(num_left != num_right))
-                }
-                (Value::Bool(bool_left), Value::Bool(bool_right)) => {
-                    Ok(Value::Bool(bool_left != bool_right))
-                }
-                (v_left, v_right) => Err(BadArgs(vec![v_left, v_right])),
-            },
-            Operator::LT => match (
-                interp_expression(env, *left, writer)?,
-                interp_expression(env, *right, writer)?,
-            ) {
-                (Value::Num(num_left), Value::Num(num_right)) => {
-                    Ok(Value::Bool(num_left < num_right))
-                }
-                (v_left, v_right) => Err(BadArgs(vec![v_left, v_right])),
-            },
-            Operator::GT => match (
-                interp_expression(env, *left, writer)?,
-                interp_expression(env, *right, writer)?,
-            ) {
-                (Value::Num(num_left), Value::Num(num_right)) => {
-                    Ok(Value::Bool(num_left > num_right))
+            bad_value => Err(ExpectedInteger(bad_value)),
+        },
+        Value::Str(str) => match interp_expression(env, index_expr, writer)? {
+            Value::Num(index) => {
+                if index < 0 {
+                    return Err(IndexOutOfBounds(index));
                 }
-                (v_left, v_right) => Err(BadArgs(vec![v_left, v_right])),
-            },
-            Operator::LTE => match (
-                interp_expression(env, *left, writer)?,
-                interp_expression(env, *right, writer)?,
-            ) {
-                (Value::Num(num_left), Value::Num(num_right)) => {
-                    Ok(Value::Bool(num_left <= num_right))
+
+                let character = match str.chars().nth(index as usize) {
+                    Some(char) => char.to_string(),
+                    None => return Err(IndexOutOfBounds(index)),
+                };
+
+                Ok(Value::Str(character))
+            }
+            bad_value => Err(ExpectedInteger(bad_value)),
+        },
+        Value::Map(entries) => {
+            let key = interp_expression(env, index_expr, writer)?;
+            match map_get(&entries, &key)? {
+                Some(value) => Ok(value),
+                None => Err(KeyNotFound(key)),
+            }
+        }
+        value => Err(NotIndexable(value)),
+    }
+}
+
+/// Interprets the binary operation `op` applied to `left` and `right`.
+fn interp_binary(
+    env: &mut Environment,
+    op: Operator,
+    left: &Expr,
+    right: &Expr,
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    let left_value = interp_expression(env, left, writer)?;
+    // the right operand of a logical operator is only evaluated if it decides the result
+    match (op, is_truthy(&left_value)) {
+        (Operator::LogicOr, true) => return Ok(Value::Bool(true)),
+        (Operator::LogicAnd, false) => return Ok(Value::Bool(false)),
+        _ => (),
+    }
+    let right_value = interp_expression(env, right, writer)?;
+    match op {
+        Operator::Eq => values_equal(left_value, right_value).map(Value::Bool),
+        Operator::Ne => values_equal(left_value, right_value).map(|equal| Value::Bool(!equal)),
+        Operator::LogicOr | Operator::LogicAnd => Ok(Value::Bool(is_truthy(&right_value))),
+        op => apply_binary(env, op, left_value, right_value),
+    }
+}
+
+/// Applies the arithmetic, relational or bitwise operator `op` to the values `left` and `right`.
+/// `nil` is not an operand of any of these operators, since it is usually the result of a call
+/// which has no value, so it is a [NilOperand] error.
+fn apply_binary(
+    env: &Environment,
+    op: Operator,
+    left: Value,
+    right: Value,
+) -> Result<Value, RuntimeError> {
+    if matches!(left, Value::Nil) || matches!(right, Value::Nil) {
+        return Err(NilOperand(op));
+    }
+    match op {
+        Operator::Plus => {
+            match (left, right) {
+                (Value::Num(num_left), Value::Num(num_right)) => integer_result(
+                    env,
+                    num_left.checked_add(num_right),
+                    num_left.wrapping_add(num_right),
+                ),
+                (Value::Str(num_left), Value::Str(num_right)) => {
+                    Ok(Value::Str(num_left + num_right.as_str()))
                 }
-                (v_left, v_right) => Err(BadArgs(vec![v_left, v_right])),
-            },
-            Operator::GTE => match (
-                interp_expression(env, *left, writer)?,
-                interp_expression(env, *right, writer)?,
-            ) {
-                (Value::Num(num_left), Value::Num(num_right)) => {
-                    Ok(Value::Bool(num_left >= num_right))
+                (Value::List(mut list_left), Value::List(mut list_right)) => {
+                    list_left.append(&mut list_right);
+                    Ok(Value::List(list_left))
                 }
-                (v_left, v_right) => Err(BadArgs(vec![v_left, v_right])),
-            },
-            Operator::LogicOr => match interp_expression(env, *left, writer)? {
-                Value::Bool(b) => match b {
-                    true => Ok(Value::Bool(true)),
-                    false => match interp_expression(env, *right, writer)? {
-                        Value::Bool(b) => Ok(Value::Bool(b)),
-                        right_value => Err(BadArg(right_value)),
-                    },
+                (v_left, v_right) => match float_operands(&v_left, &v_right) {
+                    Some((num_left, num_right)) => Ok(Value::Float(num_left + num_right)),
+                    None => Err(numeric_bad_arg(v_left, v_right)),
                 },
-                left_value => Err(BadArg(left_value)),
+            }
+        }
+        Operator::Minus => match (left, right) {
+            (Value::Num(num_left), Value::Num(num_right)) => integer_result(
+                env,
+                num_left.checked_sub(num_right),
+                num_left.wrapping_sub(num_right),
+            ),
+            (v_left, v_right) => match float_operands(&v_left, &v_right) {
+                Some((num_left, num_right)) => Ok(Value::Float(num_left - num_right)),
+                None => Err(numeric_bad_arg(v_left, v_right)),
             },
-            Operator::LogicAnd => match interp_expression(env, *left, writer)? {
-                Value::Bool(b) => match b {
-                    false => Ok(Value::Bool(false)),
-                    true => match interp_expression(env, *right, writer)? {
-                        Value::Bool(b) => Ok(Value::Bool(b)),
-                        right_value => Err(BadArg(right_value)),
-                    },
-                },
-                left_value => Err(BadArg(left_value)),
+        },
+        Operator::Times => match (left, right) {
+            (Value::Num(num_left), Value::Num(num_right)) => integer_result(
+                env,
+                num_left.checked_mul(num_right),
+                num_left.wrapping_mul(num_right),
+            ),
+            (Value::Str(s), Value::Num(count)) => match usize::try_from(count) {
+                Ok(count) => Ok(Value::Str(s.repeat(count))),
+                Err(_) => Err(BadArg(Value::Num(count))),
             },
-            Operator::Times => match (
-                interp_expression(env, *left, writer)?,
-                interp_expression(env, *right, writer)?,
-            ) {
-                (Value::Num(num_left), Value::Num(num_right)) => {
-                    Ok(Value::Num(num_left * num_right))
-                }
-                (v_left, v_right) => Err(BadArgs(vec![v_left, v_right])),
+            (Value::Str(_), count @ Value::Float(_)) => Err(BadArg(count)),
+            (v_left, v_right) => match float_operands(&v_left, &v_right) {
+                Some((num_left, num_right)) => Ok(Value::Float(num_left * num_right)),
+                None => Err(BadArgs(vec![v_left, v_right])),
             },
-            Operator::Mod => match (
-                interp_expression(env, *left, writer)?,
-                interp_expression(env, *right, writer)?,
-            ) {
-                (Value::Num(num_left), Value::Num(num_right)) => {
-                    Ok(Value::Num(num_left % num_right))
-                }
-                (v_left, v_right) => Err(BadArgs(vec![v_left, v_right])),
+        },
+        Operator::Mod => match (left, right) {
+            (Value::Num(_), Value::Num(0)) => Err(DivisionByZero),
+            (Value::Num(num_left), Value::Num(num_right)) => integer_result(
+                env,
+                num_left.checked_rem(num_right),
+                num_left.wrapping_rem(num_right),
+            ),
+            (v_left, v_right) => match float_operands(&v_left, &v_right) {
+                Some((num_left, num_right)) => Ok(Value::Float(num_left % num_right)),
+                None => Err(BadArgs(vec![v_left, v_right])),
             },
-            Operator::Div => match (
-                interp_expression(env, *left, writer)?,
-                interp_expression(env, *right, writer)?,
-            ) {
-                (Value::Num(num_left), Value::Num(num_right)) => {
-                    Ok(Value::Num(num_left / num_right))
-                }
-                (v_left, v_right) => Err(BadArgs(vec![v_left, v_right])),
+        },
+        Operator::Div => match (left, right) {
+            (Value::Num(_), Value::Num(0)) => Err(DivisionByZero),
+            (Value::Num(num_left), Value::Num(num_right)) => integer_result(
+                env,
+                num_left.checked_div(num_right),
+                num_left.wrapping_div(num_right),
+            ),
+            (v_left, v_right) => match float_operands(&v_left, &v_right) {
+                Some((num_left, num_right)) => Ok(Value::Float(num_left / num_right)),
+                None => Err(BadArgs(vec![v_left, v_right])),
             },
-            op => Err(UnaryAsBinary(op)),
         },
-        Expr::Unary(op, operand) => match op {
-            Operator::PreIncrement => {
-                let var_name = match *operand {
-                    Expr::Var(ref id) => id.to_string(),
-                    _ => return Err(InvalidAssignmentTarget),
-                };
-
-                let num_value = match interp_expression(env, *operand, writer)? {
-                    Value::Num(n) => n,
-                    v => return Err(BadArg(v)),
+        Operator::Pow => match (left, right) {
+            (Value::Num(num_left), Value::Num(num_right)) if num_right < 0 => {
+                Ok(Value::Float((num_left as f64).powf(num_right as f64)))
+            }
+            (Value::Num(num_left), Value::Num(num_right)) => {
+                let wrapped = wrapping_pow(num_left, num_right as u64);
+                let checked = match num_left {
+                    // these bases never overflow, however large the exponent
+                    -1..=1 => Some(wrapped),
+                    _ => u32::try_from(num_right)
+                        .ok()
+                        .and_then(|exponent| num_left.checked_pow(exponent)),
                 };
+                integer_result(env, checked, wrapped)
+            }
+            (v_left, v_right) => match float_operands(&v_left, &v_right) {
+                Some((num_left, num_right)) => Ok(Value::Float(num_left.powf(num_right))),
+                None => Err(BadArgs(vec![v_left, v_right])),
+            },
+        },
+        Operator::BitAnd => match (left, right) {
+            (Value::Num(num_left), Value::Num(num_right)) => {
+                Ok(Value::Num(env.int_width().wrap(num_left & num_right)))
+            }
+            (v_left, v_right) => Err(integer_bad_arg(v_left, v_right)),
+        },
+        Operator::BitOr => match (left, right) {
+            (Value::Num(num_left), Value::Num(num_right)) => {
+                Ok(Value::Num(env.int_width().wrap(num_left | num_right)))
+            }
+            (v_left, v_right) => Err(integer_bad_arg(v_left, v_right)),
+        },
+        Operator::BitXor => match (left, right) {
+            (Value::Num(num_left), Value::Num(num_right)) => {
+                Ok(Value::Num(env.int_width().wrap(num_left ^ num_right)))
+            }
+            (Value::Bool(bool_left), Value::Bool(bool_right)) => {
+                Ok(Value::Bool(bool_left ^ bool_right))
+            }
+            (v_left, v_right) => Err(integer_bad_arg(v_left, v_right)),
+        },
+        Operator::ShiftLeft => match (left, right) {
+            (Value::Num(num_left), Value::Num(num_right)) => {
+                let shifted = num_left << ensure_shift_amount(num_right)?;
+                Ok(Value::Num(env.int_width().wrap(shifted)))
+            }
+            (v_left, v_right) => Err(integer_bad_arg(v_left, v_right)),
+        },
+        Operator::ShiftRight => match (left, right) {
+            (Value::Num(num_left), Value::Num(num_right)) => {
+                let shifted = num_left >> ensure_shift_amount(num_right)?;
+                Ok(Value::Num(env.int_width().wrap(shifted)))
+            }
+            (v_left, v_right) => Err(integer_bad_arg(v_left, v_right)),
+        },
+        Operator::LT | Operator::GT | Operator::LTE | Operator::GTE => {
+            Ok(Value::Bool(compare_values(op, left, right)?))
+        }
+        op => Err(UnaryAsBinary(op)),
+    }
+}
 
-                env.reassign(var_name, Value::Num(num_value + 1.0))?;
+/// Interprets a call of the procedure that `f_expr` evaluates to with the arguments `args`.
+fn interp_call(
+    env: &mut Environment,
+    f_expr: &Expr,
+    args: &[Expr],
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    let f_name_option = match f_expr {
+        Expr::Var(f_name) => Some(f_name.to_string()),
+        _ => None,
+    };
+    let f_value = interp_expression(env, f_expr, writer)?;
+    let arg_values = interp_args(env, args, writer)?;
+    apply_proc(env, f_name_option, f_value, arg_values, writer)
+}
 
-                return Ok(Value::Num(num_value + 1.0));
+/// Evaluates the arguments `args` of a call in order, expanding each spread argument into the
+/// elements of its list.
+fn interp_args(
+    env: &mut Environment,
+    args: &[Expr],
+    writer: &mut Writer,
+) -> Result<Vec<Value>, RuntimeError> {
+    // the number of arguments is only known once every spread argument has been expanded
+    let mut arg_values = vec![];
+    for arg in args {
+        match arg {
+            Expr::Spread(list_expr) => {
+                arg_values.extend(ensure_list(interp_expression(env, list_expr, writer)?)?)
             }
-            Operator::PostIncrement => {
-                let var_name = match *operand {
-                    Expr::Var(ref id) => id.to_string(),
-                    _ => return Err(InvalidAssignmentTarget),
-                };
+            arg => arg_values.push(interp_expression(env, arg, writer)?),
+        }
+    }
+    Ok(arg_values)
+}
 
-                let original_num_value = match interp_expression(env, *operand, writer)? {
-                    Value::Num(n) => n,
-                    v => return Err(BadArg(v)),
-                };
+/// Applies the procedure `f_value` to the already evaluated `arg_values`. The name of the
+/// procedure, if it has one, is used in error messages and traces.
+pub fn apply_proc(
+    env: &mut Environment,
+    f_name_option: Option<String>,
+    f_value: Value,
+    arg_values: Vec<Value>,
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    let mut call = match enter_proc(env, f_name_option, f_value, arg_values, writer) {
+        // a return in a block expression of a default returns from the procedure being called
+        Err(BlockControlFlow(value, ControlFlow::Return)) => return Ok(value),
+        result => result?,
+    };
+
+    let (value, _) = interp_statement(&mut call.env, &call.body, false, writer)?;
+    if let Some(trace_name) = call.trace_name_option {
+        trace(env, format!("return {trace_name} => {value}"));
+    }
+    Ok(value)
+}
+
+/// A call of a procedure whose arguments have been bound to its params.
+struct ProcCall {
+    body: Box<Statement>,
+    /// The environment to interpret the body in, where each param is bound to its argument or
+    /// default. It is boxed to keep the stack frames of nested calls small.
+    env: Box<Environment>,
+    /// The name to trace the call with, if calls are being traced.
+    trace_name_option: Option<String>,
+}
+
+/// Checks that `arg_values` match the params of the procedure `f_value`, and binds each param to
+/// its argument or default.
+fn enter_proc(
+    env: &mut Environment,
+    f_name_option: Option<String>,
+    f_value: Value,
+    mut arg_values: Vec<Value>,
+    writer: &mut Writer,
+) -> Result<ProcCall, RuntimeError> {
+    let (mut f_params, f_body, mut f_env) = match f_value {
+        Value::Proc(params, body, env) => (params, body, env),
+        v => return Err(BadArg(v)),
+    };
+
+    // a variadic param is always the last param, and it collects every extra argument
+    let variadic_param_option = match f_params.last() {
+        Some(param) if param.variadic => f_params.pop(),
+        _ => None,
+    };
+
+    let required_params_count = f_params
+        .iter()
+        .take_while(|param| param.default.is_none())
+        .count();
+    let has_extra_args = variadic_param_option.is_none() && arg_values.len() > f_params.len();
+    if arg_values.len() < required_params_count || has_extra_args {
+        let expected_args_count = if arg_values.len() < required_params_count {
+            required_params_count
+        } else {
+            f_params.len()
+        };
+        return Err(ArgMismatch(
+            // an anonymous procedure is named after its params, to show what it expected
+            f_name_option.unwrap_or_else(|| {
+                anonymous_proc_name(f_params.iter().chain(&variadic_param_option))
+            }),
+            expected_args_count, // expected
+            arg_values.len(),    // actual
+        ));
+    }
+
+    let trace_name_option = match env.is_tracing() {
+        true => Some(f_name_option.unwrap_or_else(|| {
+            anonymous_proc_name(f_params.iter().chain(&variadic_param_option))
+        })),
+        false => None,
+    };
+    if let Some(trace_name) = &trace_name_option {
+        let arg_strings: Vec<String> = arg_values.iter().map(|v| v.to_string()).collect();
+        trace(env, format!("call {trace_name}({})", arg_strings.join(", ")));
+    }
+
+    let extra_arg_values = if arg_values.len() > f_params.len() {
+        arg_values.split_off(f_params.len())
+    } else {
+        vec![]
+    };
+    let args_count = arg_values.len();
+
+    let entries: Vec<Entry> = arg_values
+        .into_iter()
+        .map(|v| (v, Mutability::Constant))
+        .collect();
+
+    let param_bindings: Vec<Binding> = f_params
+        .iter()
+        .map(|param| param.name.to_string())
+        .zip(entries)
+        .collect();
+    f_env.extend(param_bindings);
+    f_env.enter_call_from(env)?;
+
+    // the defaults of omitted arguments are evaluated in order, so a default may refer to
+    // any parameter before it
+    for param in f_params.into_iter().skip(args_count) {
+        let default_expr = param
+            .default
+            .expect("omitted arguments should only correspond to params with defaults");
+        let default_value = interp_expression(&mut f_env, &default_expr, writer)?;
+        f_env.extend(vec![(param.name, (default_value, Mutability::Constant))]);
+    }
+
+    if let Some(variadic_param) = variadic_param_option {
+        f_env.extend(vec![(
+            variadic_param.name,
+            (Value::List(extra_arg_values), Mutability::Constant),
+        )]);
+    }
+
+    Ok(ProcCall {
+        body: f_body,
+        env: f_env,
+        trace_name_option,
+    })
+}
+
+/// Returns the name used for an anonymous procedure with the given params in error messages, such
+/// as `<lambda (a, ...rest)>`.
+fn anonymous_proc_name<'a>(params: impl Iterator<Item = &'a Param>) -> String {
+    let param_names: Vec<String> = params
+        .map(|param| match param.variadic {
+            true => format!("...{}", param.name),
+            false => param.name.to_string(),
+        })
+        .collect();
+    format!("<lambda ({})>", param_names.join(", "))
+}
+
+/// Interprets a call of the builtin procedure `builtin` with the arguments `args`.
+fn interp_primitive_call(
+    env: &mut Environment,
+    builtin: Builtin,
+    args: &[Expr],
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    ensure_arity(builtin, args)?;
+    // every argument is evaluated before the builtin is applied, so that the large stack frame of
+    // applying a builtin is never on the stack while an argument makes nested calls
+    match builtin {
+        Builtin::Assert => interp_assert(env, args, writer),
+        Builtin::Map | Builtin::Filter | Builtin::Reduce | Builtin::Sort => {
+            let arg_values = interp_args(env, args, writer)?;
+            apply_higher_order_builtin(env, builtin, arg_values, writer)
+        }
+        builtin => {
+            let arg_values = interp_args(env, args, writer)?;
+            apply_builtin(env, builtin, arg_values, writer)
+        }
+    }
+}
+
+/// Interprets a call of `assert` with the arguments `args`. Unlike the arguments of other
+/// builtins, the message is only evaluated when the assertion fails.
+fn interp_assert(
+    env: &mut Environment,
+    args: &[Expr],
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    let (cond_expr, message_expr_option) = match args {
+        [cond_expr] => (cond_expr, None),
+        [cond_expr, message_expr] => (cond_expr, Some(message_expr)),
+        _ => unreachable!("the arity of {} should have been checked", Builtin::Assert),
+    };
 
-                env.reassign(var_name, Value::Num(original_num_value + 1.0))?;
+    if is_truthy(&interp_expression(env, cond_expr, writer)?) {
+        return Ok(Value::Nil);
+    }
+
+    let message_option = match message_expr_option {
+        Some(message_expr) => match interp_expression(env, message_expr, writer)? {
+            Value::Str(message) => Some(message),
+            v => return Err(BadArg(v)),
+        },
+        None => None,
+    };
+    Err(AssertionFailed(message_option))
+}
+
+/// Applies `builtin`, which calls the procedure it is passed, to the evaluated `args`.
+fn apply_higher_order_builtin(
+    env: &mut Environment,
+    builtin: Builtin,
+    args: Vec<Value>,
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    match builtin {
+        Builtin::Map => {
+            let [list_value, f_value] = ensure_args(builtin, args)?;
+            let list = ensure_list(list_value)?;
 
-                return Ok(Value::Num(original_num_value));
+            let mut mapped = vec![];
+            for element in list {
+                mapped.push(apply_proc(env, None, f_value.clone(), vec![element], writer)?);
             }
-            Operator::PreDecrement => {
-                let var_name = match *operand {
-                    Expr::Var(ref id) => id.to_string(),
-                    _ => return Err(InvalidAssignmentTarget),
-                };
+            Ok(Value::List(mapped))
+        }
+        Builtin::Filter => {
+            let [list_value, predicate_value] = ensure_args(builtin, args)?;
+            let list = ensure_list(list_value)?;
 
-                let num_value = match interp_expression(env, *operand, writer)? {
-                    Value::Num(n) => n,
-                    v => return Err(BadArg(v)),
-                };
+            let mut filtered = vec![];
+            for element in list {
+                let arg_values = vec![element.clone()];
+                let keep = apply_proc(env, None, predicate_value.clone(), arg_values, writer)?;
+                if is_truthy(&keep) {
+                    filtered.push(element);
+                }
+            }
+            Ok(Value::List(filtered))
+        }
+        Builtin::Reduce => {
+            let [list_value, f_value, mut accumulator] = ensure_args(builtin, args)?;
+            let list = ensure_list(list_value)?;
 
-                env.reassign(var_name, Value::Num(num_value - 1.0))?;
+            for element in list {
+                let arg_values = vec![accumulator, element];
+                accumulator = apply_proc(env, None, f_value.clone(), arg_values, writer)?;
+            }
+            Ok(accumulator)
+        }
+        Builtin::Sort => {
+            let mut args = args.into_iter();
+            let list_value = args.next().expect("the arity of sort should ensure it has a list");
+            let list = ensure_list(list_value)?;
 
-                return Ok(Value::Num(num_value - 1.0));
+            match args.next() {
+                Some(compare_value) => {
+                    // the comparator returns a negative, zero, or positive number when its first
+                    // argument should come before, with, or after its second argument
+                    let sorted = merge_sort(list, &mut |left, right| {
+                        let arg_values = vec![left.clone(), right.clone()];
+                        match apply_proc(env, None, compare_value.clone(), arg_values, writer)? {
+                            Value::Num(n) => Ok(n.cmp(&0)),
+                            Value::Float(n) => n.partial_cmp(&0.0).ok_or(BadArg(Value::Float(n))),
+                            v => Err(BadArg(v)),
+                        }
+                    })?;
+                    Ok(Value::List(sorted))
+                }
+                None => Ok(Value::List(merge_sort(list, &mut default_order)?)),
             }
-            Operator::PostDecrement => {
-                let var_name = match *operand {
-                    Expr::Var(ref id) => id.to_string(),
-                    _ => return Err(InvalidAssignmentTarget),
-                };
+        }
+        builtin => unreachable!("{builtin} does not call a procedure"),
+    }
+}
 
-                let original_num_value = match interp_expression(env, *operand, writer)? {
-                    Value::Num(n) => n,
-                    v => return Err(BadArg(v)),
-                };
+/// Applies `builtin` to the evaluated `args`.
+fn apply_builtin(
+    env: &mut Environment,
+    builtin: Builtin,
+    args: Vec<Value>,
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    match builtin {
+        Builtin::Print => {
+            let values: Vec<String> = args.iter().map(|v| v.to_string()).collect();
+            let values = values.join(" ");
 
-                env.reassign(var_name, Value::Num(original_num_value - 1.0))?;
+            // TODO: write a better expect message
+            writer.w.write_all(values.as_bytes()).expect("should be able to write");
+            // print!("{}", values);
+            Ok(Value::Nil)
+        }
+        Builtin::List => Ok(Value::List(args)),
+        Builtin::IsEmpty => {
+            let [arg] = ensure_args(builtin, args)?;
+            let list = ensure_list(arg)?;
+            Ok(Value::Bool(list.is_empty()))
+        }
+        Builtin::IsNil => {
+            let [arg] = ensure_args(builtin, args)?;
+            match arg {
+                Value::Nil => Ok(Value::Bool(true)),
+                _ => Ok(Value::Bool(false)),
+            }
+        }
+        Builtin::Head => {
+            let [arg] = ensure_args(builtin, args)?;
+            let list = ensure_list(arg)?;
 
-                return Ok(Value::Num(original_num_value));
+            match list.as_slice() {
+                [hd, ..] => Ok(hd.clone()),
+                [] => Ok(Value::Nil),
             }
-            Operator::Minus => match interp_expression(env, *operand, writer)? {
-                Value::Num(n) => Ok(Value::Num(-n)),
-                v => Err(BadArg(v)),
-            },
-            Operator::LogicNot => match interp_expression(env, *operand, writer)? {
-                Value::Bool(b) => Ok(Value::Bool(!b)),
+        }
+        Builtin::Rest => {
+            let [arg] = ensure_args(builtin, args)?;
+            let list = ensure_list(arg)?;
+
+            match list.as_slice() {
+                [_, tail @ ..] => Ok(Value::List(tail.to_vec())),
+                [] => Ok(Value::Nil),
+            }
+        }
+        Builtin::Len => {
+            let [arg] = ensure_args(builtin, args)?;
+            match arg {
+                Value::Str(s) => Ok(Value::Num(s.chars().count() as i64)),
                 v => Err(BadArg(v)),
-            },
-            op => Err(BinaryAsUnary(op)),
-        },
-        Expr::Call(f_expr, args) => {
-            let f_name = match *f_expr {
-                Expr::Var(ref f_name) => f_name.to_string(),
-                _ => "<lambda>".to_string(),
-            };
+            }
+        }
+        Builtin::ReadLine => {
+            ensure_no_args(builtin, &args)?;
+
+            // flush any prompt written without a trailing newline before blocking on input
+            writer.w.flush().expect("should be able to flush");
 
-            let (f_params, f_body, f_env) = match interp_expression(env, *f_expr, writer)? {
-                Value::Proc(params, body, env) => (params, body, env),
+            let mut line = String::new();
+            if let Err(e) = stdin().read_line(&mut line) {
+                return Err(ReadFailed(e.to_string()));
+            }
+            if line.ends_with('\n') {
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+            }
+            Ok(Value::Str(line))
+        }
+        Builtin::ToNum => {
+            let [arg] = ensure_args(builtin, args)?;
+            let s = match arg {
+                Value::Str(s) => s,
                 v => return Err(BadArg(v)),
             };
 
-            if args.len() != f_params.len() {
-                return Err(ArgMismatch(
-                    f_name.to_string(),
-                    f_params.len(), // expected
-                    args.len(),     // actual
-                ));
+            let trimmed = s.trim();
+            let parsed = if trimmed.contains('.') {
+                trimmed.parse::<f64>().ok().map(Value::Float)
+            } else {
+                trimmed.parse::<i64>().ok().map(Value::Num)
+            };
+            match parsed {
+                Some(value) => Ok(value),
+                None => Err(ParseFailure(s, "a number")),
+            }
+        }
+        Builtin::ToStr => {
+            let [arg] = ensure_args(builtin, args)?;
+            Ok(Value::Str(arg.to_string()))
+        }
+        // builtins are not values, so `typeof(print)` is an unknown variable error
+        Builtin::TypeOf => {
+            let [arg] = ensure_args(builtin, args)?;
+            Ok(Value::Str(arg.type_name().to_string()))
+        }
+        Builtin::Push => {
+            let [list_value, value] = ensure_args(builtin, args)?;
+            let mut list = ensure_list(list_value)?;
+            list.push(value);
+            Ok(Value::List(list))
+        }
+        Builtin::Pop => {
+            let [arg] = ensure_args(builtin, args)?;
+            let mut list = ensure_list(arg)?;
+            match list.pop() {
+                Some(value) => Ok(value),
+                None => Err(EmptyList),
+            }
+        }
+        Builtin::Concat => {
+            let [left_value, right_value] = ensure_args(builtin, args)?;
+            let mut left_list = ensure_list(left_value)?;
+            let mut right_list = ensure_list(right_value)?;
+            left_list.append(&mut right_list);
+            Ok(Value::List(left_list))
+        }
+        Builtin::MapOf => {
+            if !args.len().is_multiple_of(2) {
+                return Err(ArgMismatch(builtin.to_string(), args.len() + 1, args.len()));
             }
 
-            let arg_values_result: Result<Vec<Value>, RuntimeError> = args
-                .into_iter()
-                .map(|arg| interp_expression(env, arg, writer))
-                .collect();
-            let arg_values = match arg_values_result {
-                Ok(values) => values,
-                Err(e) => return Err(e),
+            // the arguments alternate between keys and values
+            let mut entries = vec![];
+            let mut args = args.into_iter();
+            while let (Some(key), Some(value)) = (args.next(), args.next()) {
+                map_insert(&mut entries, key, value)?;
+            }
+            Ok(Value::Map(entries))
+        }
+        Builtin::HasKey => {
+            let [map_value, key] = ensure_args(builtin, args)?;
+            let entries = ensure_map(map_value)?;
+            Ok(Value::Bool(map_get(&entries, &key)?.is_some()))
+        }
+        Builtin::Keys => {
+            let [arg] = ensure_args(builtin, args)?;
+            let entries = ensure_map(arg)?;
+            Ok(Value::List(entries.into_iter().map(|(key, _)| key).collect()))
+        }
+        Builtin::Values => {
+            let [arg] = ensure_args(builtin, args)?;
+            let entries = ensure_map(arg)?;
+            Ok(Value::List(entries.into_iter().map(|(_, value)| value).collect()))
+        }
+        Builtin::DestructureLength => {
+            let [list_value, length_value] = ensure_args(builtin, args)?;
+            let list = ensure_list(list_value)?;
+            let length = ensure_integer(length_value)?;
+            if list.len() as i64 != length {
+                return Err(DestructureMismatch(length as usize, list.len()));
+            }
+            Ok(Value::Nil)
+        }
+        Builtin::Substr => {
+            let [str_value, start_value, len_value] = ensure_args(builtin, args)?;
+            let str = match str_value {
+                Value::Str(str) => str,
+                v => return Err(BadArg(v)),
             };
+            let start = ensure_integer(start_value)?;
+            let len = ensure_integer(len_value)?;
 
-            let entries: Vec<Entry> = arg_values
-                .into_iter()
-                .map(|v| (v, AssignmentType::Initialized, Mutability::Constant))
-                .collect();
+            // the substring may start just past the last character, in which case it is empty
+            if start < 0 || start as usize > str.chars().count() {
+                return Err(IndexOutOfBounds(start));
+            }
+            if len < 0 {
+                return Err(BadArg(Value::Num(len)));
+            }
 
-            let param_bindings: Vec<Binding> = f_params
-                .iter()
-                .map(|param| param.to_string())
-                .zip(entries)
-                .collect();
+            let substr = str.chars().skip(start as usize).take(len as usize).collect();
+            Ok(Value::Str(substr))
+        }
+        Builtin::Split => {
+            let [str_value, separator_value] = ensure_args(builtin, args)?;
+            let str = ensure_string(str_value)?;
+            let separator = ensure_string(separator_value)?;
 
-            return match interp_statement(&mut f_env.extend(param_bindings), f_body, false, writer)? {
-                (value, _) => Ok(value),
+            // an empty separator splits the string into its characters
+            let pieces = if separator.is_empty() {
+                str.chars().map(|c| Value::Str(c.to_string())).collect()
+            } else {
+                str.split(separator.as_str())
+                    .map(|piece| Value::Str(piece.to_string()))
+                    .collect()
             };
+            Ok(Value::List(pieces))
         }
-        Expr::PrimitiveCall(builtin, args) => match builtin {
-            crate::parser::Builtin::Print => {
-                let mut values: Vec<Value> = vec![];
-                for expr in args {
-                    values.push(interp_expression(env, expr, writer)?);
-                }
-                let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
-                let values = values.join(" ");
-
-                // TODO: write a better expect message
-                writer.w.write(values.as_bytes()).expect("should be able to write");
-                // print!("{}", values);
-                Ok(Value::Nil)
-            }
-            crate::parser::Builtin::List => {
-                let mut values = vec![];
-                for expr in args {
-                    values.push(interp_expression(env, expr, writer)?);
-                }
-                Ok(Value::List(values))
-            }
-            crate::parser::Builtin::IsEmpty => {
-                let arg = ensure_single_arg(args)?;
-                let list = ensure_list(interp_expression(env, arg, writer)?)?;
-                Ok(Value::Bool(list.is_empty()))
-            }
-            crate::parser::Builtin::IsNil => {
-                let arg = ensure_single_arg(args)?;
-                match interp_expression(env, arg, writer)? {
-                    Value::Nil => Ok(Value::Bool(true)),
-                    _ => Ok(Value::Bool(false)),
-                }
-            }
-            crate::parser::Builtin::Head => {
-                let arg = ensure_single_arg(args)?;
-                let list = ensure_list(interp_expression(env, arg, writer)?)?;
+        Builtin::Join => {
+            let [list_value, separator_value] = ensure_args(builtin, args)?;
+            let list = ensure_list(list_value)?;
+            let separator = ensure_string(separator_value)?;
 
-                match list.as_slice() {
-                    [hd, ..] => Ok(hd.clone()),
-                    [] => Ok(Value::Nil),
+            let pieces = list
+                .into_iter()
+                .map(ensure_string)
+                .collect::<Result<Vec<String>, RuntimeError>>()?;
+            Ok(Value::Str(pieces.join(separator.as_str())))
+        }
+        Builtin::Abs => {
+            let [arg] = ensure_args(builtin, args)?;
+            match arg {
+                Value::Num(n) => integer_result(env, n.checked_abs(), n.wrapping_abs()),
+                Value::Float(n) => Ok(Value::Float(n.abs())),
+                v => Err(BadArg(v)),
+            }
+        }
+        Builtin::Min | Builtin::Max => {
+            let [left_value, right_value] = ensure_args(builtin, args)?;
+            let is_min = builtin == Builtin::Min;
+            match (left_value, right_value) {
+                (Value::Num(num_left), Value::Num(num_right)) => Ok(Value::Num(if is_min {
+                    num_left.min(num_right)
+                } else {
+                    num_left.max(num_right)
+                })),
+                // an integer compared with a float is promoted to a float
+                (v_left, v_right) => match float_operands(&v_left, &v_right) {
+                    Some((num_left, num_right)) => Ok(Value::Float(if is_min {
+                        num_left.min(num_right)
+                    } else {
+                        num_left.max(num_right)
+                    })),
+                    None => Err(numeric_bad_arg(v_left, v_right)),
+                },
+            }
+        }
+        Builtin::FloorDiv => {
+            let [left_value, right_value] = ensure_args(builtin, args)?;
+            match (left_value, right_value) {
+                (Value::Num(_), Value::Num(0)) => Err(DivisionByZero),
+                (Value::Num(num_left), Value::Num(num_right)) => {
+                    // the quotient only overflows when it is exact, so it wraps like a division
+                    let quotient_option = checked_floor_div(num_left, num_right);
+                    let wrapping = quotient_option.unwrap_or(num_left.wrapping_div(num_right));
+                    integer_result(env, quotient_option, wrapping)
                 }
+                (v_left, v_right) => match float_operands(&v_left, &v_right) {
+                    Some((num_left, num_right)) => {
+                        Ok(Value::Float((num_left / num_right).floor()))
+                    }
+                    None => Err(numeric_bad_arg(v_left, v_right)),
+                },
             }
-            crate::parser::Builtin::Rest => {
-                let arg = ensure_single_arg(args)?;
-                let list = ensure_list(interp_expression(env, arg, writer)?)?;
+        }
+        Builtin::Vars => {
+            ensure_no_args(builtin, &args)?;
 
-                match list.as_slice() {
-                    [_, tail @ ..] => Ok(Value::List(tail.to_vec())),
-                    [] => Ok(Value::Nil),
+            // names introduced by desugaring start with '#' and are not visible to the program
+            let mut names: Vec<&str> = env.names().filter(|name| !name.starts_with('#')).collect();
+            names.sort();
+            Ok(Value::List(names.into_iter().map(|name| Value::Str(name.to_string())).collect()))
+        }
+        Builtin::Exit => {
+            let [arg] = ensure_args(builtin, args)?;
+            let code = ensure_integer(arg)?;
+            // a code outside of the range of an exit status would be changed by the process, and a
+            // negative code could even be reported as success
+            if !(0..=255).contains(&code) {
+                return Err(BadArg(Value::Num(code)));
+            }
+            Err(Exit(code))
+        }
+        Builtin::Floor | Builtin::Ceil | Builtin::Round => {
+            let [arg] = ensure_args(builtin, args)?;
+            match arg {
+                Value::Num(n) => Ok(Value::Num(n)),
+                Value::Float(n) => {
+                    let rounded = match builtin {
+                        Builtin::Floor => n.floor(),
+                        Builtin::Ceil => n.ceil(),
+                        _ => n.round(),
+                    };
+                    float_to_integer(rounded).map(Value::Num)
                 }
+                v => Err(BadArg(v)),
             }
-        },
-        Expr::Index(indexable_expr, index_expr) => match interp_expression(env, *indexable_expr, writer)? {
-            Value::List(list) => match interp_expression(env, *index_expr, writer)? {
-                Value::Num(num) => {
-                    if num.fract() != 0.0 {
-                        return Err(ExpectedInteger(Value::Num(num)));
+        }
+        Builtin::Upper | Builtin::Lower | Builtin::Trim => {
+            let [arg] = ensure_args(builtin, args)?;
+            let str = ensure_string(arg)?;
+            Ok(Value::Str(match builtin {
+                Builtin::Upper => str.to_uppercase(),
+                Builtin::Lower => str.to_lowercase(),
+                _ => str.trim().to_string(),
+            }))
+        }
+        Builtin::Contains | Builtin::StartsWith | Builtin::EndsWith => {
+            let [str_value, pattern_value] = ensure_args(builtin, args)?;
+            let str = ensure_string(str_value)?;
+            let pattern = ensure_string(pattern_value)?;
+            Ok(Value::Bool(match builtin {
+                Builtin::Contains => str.contains(pattern.as_str()),
+                Builtin::StartsWith => str.starts_with(pattern.as_str()),
+                _ => str.ends_with(pattern.as_str()),
+            }))
+        }
+        Builtin::Clamp => {
+            let [value, low, high] = ensure_args(builtin, args)?;
+            match (value, low, high) {
+                (Value::Num(n), Value::Num(low), Value::Num(high)) => {
+                    if low > high {
+                        return Err(BadArgs(vec![Value::Num(low), Value::Num(high)]));
                     }
+                    Ok(Value::Num(n.clamp(low, high)))
+                }
+                // if any argument is a float, every argument is promoted to a float
+                (value, low, high) => {
+                    let n = ensure_float(value)?;
+                    let low_float = ensure_float(low.clone())?;
+                    let high_float = ensure_float(high.clone())?;
+                    // NaN bounds are unordered, so they are rejected along with reversed bounds
+                    let bounds_ordering = low_float.partial_cmp(&high_float);
+                    if matches!(bounds_ordering, None | Some(Ordering::Greater)) {
+                        return Err(BadArgs(vec![low, high]));
+                    }
+                    Ok(Value::Float(n.clamp(low_float, high_float)))
+                }
+            }
+        }
+        Builtin::Sign => {
+            let [arg] = ensure_args(builtin, args)?;
+            match arg {
+                Value::Num(n) => Ok(Value::Num(n.signum())),
+                // the orderings less, equal and greater convert to -1, 0 and 1
+                Value::Float(n) => match n.partial_cmp(&0.0) {
+                    Some(ordering) => Ok(Value::Num(ordering as i64)),
+                    None => Err(BadArg(Value::Float(n))),
+                },
+                v => Err(BadArg(v)),
+            }
+        }
+        Builtin::Range => {
+            let mut args = args.into_iter().map(ensure_integer);
+            let start = args.next().expect("the arity of range should ensure it has a start")?;
+            let end = args.next().expect("the arity of range should ensure it has an end")?;
 
-                    let index = num as i64;
-                    if index < 0 {
-                        return Err(IndexOutOfBounds(index));
+            // an explicit step must move from the start towards the end, while the default step
+            // gives an empty list when the start is not before the end
+            let step = match args.next() {
+                Some(step_result) => {
+                    let step = step_result?;
+                    if step == 0 || (start < end && step < 0) || (start > end && step > 0) {
+                        return Err(BadArg(Value::Num(step)));
                     }
+                    step
+                }
+                None => 1,
+            };
 
-                    let value = match list.into_iter().nth(index as usize) {
-                        Some(v) => v,
-                        None => return Err(IndexOutOfBounds(index)),
-                    };
+            let mut values = vec![];
+            let mut current = Some(start);
+            while let Some(n) = current.filter(|&n| if step > 0 { n < end } else { n > end }) {
+                values.push(Value::Num(n));
+                current = n.checked_add(step);
+            }
+            Ok(Value::List(values))
+        }
+        Builtin::Now => {
+            ensure_no_args(builtin, &args)?;
+            // a clock set before the unix epoch is treated as being at the epoch
+            let since_epoch = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            Ok(Value::Num(since_epoch.as_millis() as i64))
+        }
+        Builtin::Sleep => {
+            let [value] = ensure_args(builtin, args)?;
+            let millis = match value {
+                Value::Num(n) => n as f64,
+                Value::Float(n) => n,
+                v => return Err(BadArg(v)),
+            };
+            // a negative, NaN or infinite duration, or one too long to represent, is rejected
+            match Duration::try_from_secs_f64(millis / 1000.0) {
+                Ok(duration) => thread::sleep(duration),
+                Err(_) => return Err(BadArg(value)),
+            }
+            Ok(Value::Nil)
+        }
+        Builtin::Format => {
+            let args_count = args.len();
+            let mut args = args.into_iter();
+            let format_value = args
+                .next()
+                .expect("the arity of format should ensure that it has a format string");
+            let format_string = ensure_string(format_value)?;
+            let values: Vec<Value> = args.collect();
 
-                    return Ok(value);
+            // each `{}` is replaced by the next value, so there must be exactly one value for
+            // every placeholder
+            let pieces: Vec<&str> = format_string.split("{}").collect();
+            if pieces.len() - 1 != values.len() {
+                return Err(ArgMismatch(builtin.to_string(), pieces.len(), args_count));
+            }
+            let mut formatted = pieces[0].to_string();
+            for (value, piece) in values.iter().zip(&pieces[1..]) {
+                formatted.push_str(&value.to_string());
+                formatted.push_str(piece);
+            }
+            Ok(Value::Str(formatted))
+        }
+        Builtin::PrintSep => {
+            let mut args = args.into_iter();
+            let separator_value = args
+                .next()
+                .expect("the arity of print_sep should ensure that it has a separator");
+            let separator = ensure_string(separator_value)?;
+            let values: Vec<String> = args.map(|value| value.to_string()).collect();
+            writer
+                .w
+                .write_all(values.join(separator.as_str()).as_bytes())
+                .expect("should be able to write");
+            Ok(Value::Nil)
+        }
+        Builtin::Chr => {
+            let [arg] = ensure_args(builtin, args)?;
+            let codepoint = ensure_integer(arg)?;
+            // surrogates and numbers past the last codepoint do not encode a char
+            match u32::try_from(codepoint).ok().and_then(char::from_u32) {
+                Some(c) => Ok(Value::Char(c)),
+                None => Err(BadArg(Value::Num(codepoint))),
+            }
+        }
+        Builtin::Ord => {
+            let [arg] = ensure_args(builtin, args)?;
+            match arg {
+                Value::Char(c) => Ok(Value::Num(c as i64)),
+                Value::Str(s) if s.chars().count() == 1 => {
+                    let c = s.chars().next().expect("the string should have one char");
+                    Ok(Value::Num(c as i64))
                 }
-                bad_value => return Err(ExpectedInteger(bad_value)),
-            },
-            Value::Str(str) => match interp_expression(env, *index_expr, writer)? {
-                Value::Num(num) => {
-                    if num.fract() != 0.0 {
-                        return Err(ExpectedInteger(Value::Num(num)));
-                    }
+                v => Err(BadArg(v)),
+            }
+        }
+        Builtin::ToBool => {
+            let [arg] = ensure_args(builtin, args)?;
+            match arg {
+                Value::Str(s) => match s.trim() {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    _ => Err(ParseFailure(s, "a bool")),
+                },
+                v => Ok(Value::Bool(is_truthy(&v))),
+            }
+        }
+        Builtin::Repr => {
+            let [arg] = ensure_args(builtin, args)?;
+            Ok(Value::Str(arg.debug_repr()))
+        }
+        Builtin::Assert | Builtin::Map | Builtin::Filter | Builtin::Reduce | Builtin::Sort => {
+            unreachable!("{builtin} is interpreted by a function of its own")
+        }
+    }
+}
 
-                    let index = num as i64;
-                    if index < 0 {
-                        return Err(IndexOutOfBounds(index));
-                    }
+/// Adds one to or subtracts one from the variable referenced by `operand`, depending on the
+/// increment or decrement operator `op`. If `op` is a prefix operator, the updated value is
+/// returned, otherwise the value from before the update is returned.
+fn interp_increment(
+    env: &mut Environment,
+    op: Operator,
+    operand: &Expr,
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    let (delta, is_prefix) = match op {
+        Operator::PreIncrement => (1, true),
+        Operator::PostIncrement => (1, false),
+        Operator::PreDecrement => (-1, true),
+        Operator::PostDecrement => (-1, false),
+        op => unreachable!("{op} is not an increment or decrement operator"),
+    };
 
-                    let character = match str.chars().nth(index as usize) {
-                        Some(char) => char.to_string(),
-                        None => return Err(IndexOutOfBounds(index)),
-                    };
+    let var_name = match operand {
+        Expr::Var(id) => id,
+        _ => return Err(InvalidAssignmentTarget),
+    };
 
-                    return Ok(Value::Str(character));
-                }
-                bad_value => return Err(ExpectedInteger(bad_value)),
-            },
-            value => return Err(NotIndexable(value)),
-        },
+    let original_value = interp_expression(env, operand, writer)?;
+    let updated_value = match original_value {
+        Value::Num(n) => integer_result(env, n.checked_add(delta), n.wrapping_add(delta))?,
+        Value::Float(n) => Value::Float(n + delta as f64),
+        Value::Nil => return Err(NilOperand(op)),
+        v => return Err(BadArg(v)),
+    };
+
+    env.reassign(var_name, updated_value.clone())?;
+
+    if is_prefix {
+        Ok(updated_value)
+    } else {
+        Ok(original_value)
     }
 }
@@ -1,10 +1,15 @@
 use crate::{
-    desugar::Statement,
-    environment::Environment,
-    error::RuntimeError::{self, *}, Writer,
+    desugar::{Expr, Statement},
+    environment::{Environment, Mutability},
+    error::RuntimeError::{self, *},
+    Writer,
 };
 
-use super::{expressions::interp_expression, Value};
+use super::{
+    expressions::interp_expression,
+    utils::{assign_at_index, ensure_integer, is_truthy, trace},
+    Value,
+};
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum ControlFlow {
@@ -16,96 +21,226 @@ pub enum ControlFlow {
 
 pub fn interp_statement(
     env: &mut Environment,
-    statement: Statement,
+    statement: &Statement,
+    in_loop: bool,
+    writer: &mut Writer
+) -> Result<(Value, ControlFlow), RuntimeError> {
+    let position_option = statement.position();
+    // blocks are not traced themselves, since each of their statements is
+    let trace_summary_option = match statement {
+        Statement::Block(_) => None,
+        _ if env.is_tracing() => Some(trace_summary(statement)),
+        _ => None,
+    };
+
+    // a return, break, or continue in a block expression leaves the statement containing the block
+    let result = match interp_statement_helper(env, statement, in_loop, writer) {
+        Err(BlockControlFlow(value, control_flow)) => Ok((value, control_flow)),
+        result => result,
+    };
+    if let (Some(summary), Ok((value, control_flow))) = (trace_summary_option, &result) {
+        trace_result(env, summary, value, *control_flow);
+    }
+    match position_option {
+        Some(position) => result.map_err(|e| e.at(position)),
+        None => result,
+    }
+}
+
+/// Traces the value and control flow that the statement summarised by `summary` produced.
+fn trace_result(env: &Environment, summary: String, value: &Value, control_flow: ControlFlow) {
+    trace(env, format!("{summary} => {value} ({control_flow:?})"));
+}
+
+/// Returns the first line of `statement`, which is enough to identify it in a trace.
+fn trace_summary(statement: &Statement) -> String {
+    let statement_string = statement.to_string();
+    let first_line = statement_string.lines().next().unwrap_or_default();
+    match statement.position() {
+        Some((line, col)) => format!("({line}, {col}) {first_line}"),
+        None => first_line.to_string(),
+    }
+}
+
+fn interp_statement_helper(
+    env: &mut Environment,
+    statement: &Statement,
     in_loop: bool,
     writer: &mut Writer
 ) -> Result<(Value, ControlFlow), RuntimeError> {
+    // this function is entered for every nested statement, so most kinds of statement are
+    // interpreted by a function of their own, which keeps the stack frame of this one small
     match statement {
-        Statement::Expr(expr) => match interp_expression(env, expr, writer)? {
-            value => Ok((value, ControlFlow::Normal)),
-        },
-        Statement::Let(id, new_expr) => {
-            let new_value = interp_expression(env, new_expr, writer)?;
-            env.insert_new_mutable_value(id, new_value);
-            Ok((Value::Nil, ControlFlow::Normal))
+        Statement::Expr(expr, _) => {
+            interp_expression(env, expr, writer).map(|value| (value, ControlFlow::Normal))
         }
-        Statement::Const(id, new_expr) => {
-            let new_value = interp_expression(env, new_expr, writer)?;
-            env.insert_new_constant_value(id, new_value);
-            Ok((Value::Nil, ControlFlow::Normal))
+        Statement::Let(id, new_expr, _) => {
+            interp_declaration(env, id, new_expr, Mutability::Mutable, writer)
         }
-        Statement::Assign(id, expr) => {
-            let value = interp_expression(env, expr, writer)?;
-            env.reassign(id, value)?;
-            Ok((Value::Nil, ControlFlow::Normal))
+        Statement::Const(id, new_expr, _) => {
+            interp_declaration(env, id, new_expr, Mutability::Constant, writer)
         }
-        Statement::If(cond_expr, then_statement, else_statement_option) => {
-            let cond_bool = match interp_expression(env, cond_expr, writer)? {
-                Value::Bool(b) => b,
-                v => return Err(BadArg(v)),
-            };
-            if cond_bool {
-                interp_statement(env, *then_statement, in_loop, writer)
-            } else {
-                match else_statement_option {
-                    Some(else_statement) => interp_statement(env, *else_statement, in_loop, writer),
-                    None => Ok((Value::Nil, ControlFlow::Normal)),
-                }
-            }
+        Statement::Assign(id, expr, _) => interp_assign(env, id, expr, writer),
+        Statement::IndexAssign(id, index_exprs, expr, _) => {
+            interp_index_assign(env, id, index_exprs, expr, writer)
         }
-        Statement::While(cond_expr, while_block) => Ok(loop {
-            let cond_bool = match interp_expression(env, cond_expr.clone(), writer)? {
-                Value::Bool(b) => b,
-                v => return Err(BadArg(v)),
-            };
-            if cond_bool {
-                match interp_statement(env, *while_block.clone(), true, writer)? {
-                    (value, ControlFlow::Return) => break (value, ControlFlow::Return),
-                    (_, ControlFlow::Break) => break (Value::Nil, ControlFlow::Normal),
-                    (_, ControlFlow::Normal) => (),
-                    (_, ControlFlow::Continue) => (),
-                };
-            } else {
-                break (Value::Nil, ControlFlow::Normal);
-            }
-        }),
-        Statement::Return(expr_option) => match expr_option {
-            Some(expr) => Ok((interp_expression(env, expr, writer)?, ControlFlow::Return)),
-            None => Ok((Value::Nil, ControlFlow::Return)),
-        },
+        Statement::If(cond_expr, then_statement, else_statement_option, _) => {
+            let else_statement_option = else_statement_option.as_deref();
+            interp_if(env, cond_expr, then_statement, else_statement_option, in_loop, writer)
+        }
+        Statement::While(cond_expr, while_block, update_statement_option, else_block_option, _) => {
+            let update_statement_option = update_statement_option.as_deref();
+            let else_block_option = else_block_option.as_deref();
+            interp_while(
+                env,
+                cond_expr,
+                while_block,
+                update_statement_option,
+                else_block_option,
+                in_loop,
+                writer,
+            )
+        }
+        Statement::Return(Some(expr), _) => {
+            interp_expression(env, expr, writer).map(|value| (value, ControlFlow::Return))
+        }
+        Statement::Return(None, _) => Ok((Value::Nil, ControlFlow::Return)),
         Statement::Break => Ok((Value::Nil, ControlFlow::Break)),
         Statement::Continue => Ok((Value::Nil, ControlFlow::Continue)),
-        Statement::Block(statements) => {
-            let mut block_value = Value::Nil;
-            let mut block_env = env.clone();
-            for statement in statements {
-                let statement_value = match interp_statement(&mut block_env, statement, in_loop, writer)? {
-                    (value, ControlFlow::Normal) => value,
-                    (value, ControlFlow::Return) => {
-                        env.update_reassigned_entries(&block_env)?;
-                        return Ok((value, ControlFlow::Return));
-                    }
-                    (value, ControlFlow::Break) => {
-                        if in_loop {
-                            env.update_reassigned_entries(&block_env)?;
-                            return Ok((value, ControlFlow::Break));
-                        } else {
-                            return Err(BreakNotInLoop);
-                        }
-                    }
-                    (value, ControlFlow::Continue) => {
-                        if in_loop {
-                            env.update_reassigned_entries(&block_env)?;
-                            return Ok((value, ControlFlow::Continue));
-                        } else {
-                            return Err(ContinueNotInLoop);
-                        }
-                    }
-                };
-                block_value = statement_value;
+        Statement::Block(statements) => interp_block(env, statements, in_loop, writer),
+    }
+}
+
+/// Declares `id` in the current scope with the value of `new_expr`.
+fn interp_declaration(
+    env: &mut Environment,
+    id: &str,
+    new_expr: &Expr,
+    mutability: Mutability,
+    writer: &mut Writer
+) -> Result<(Value, ControlFlow), RuntimeError> {
+    let new_value = interp_expression(env, new_expr, writer)?;
+    match mutability {
+        Mutability::Mutable => env.insert_new_mutable_value(id, new_value)?,
+        Mutability::Constant => env.insert_new_constant_value(id, new_value)?,
+    }
+    Ok((Value::Nil, ControlFlow::Normal))
+}
+
+/// Assigns the value of `expr` to the variable `id`.
+fn interp_assign(
+    env: &mut Environment,
+    id: &str,
+    expr: &Expr,
+    writer: &mut Writer
+) -> Result<(Value, ControlFlow), RuntimeError> {
+    let value = interp_expression(env, expr, writer)?;
+    env.reassign(id, value)?;
+    Ok((Value::Nil, ControlFlow::Normal))
+}
+
+/// Assigns the value of `expr` to the element of the variable `id` at the indices that
+/// `index_exprs` evaluate to.
+fn interp_index_assign(
+    env: &mut Environment,
+    id: &str,
+    index_exprs: &[Expr],
+    expr: &Expr,
+    writer: &mut Writer
+) -> Result<(Value, ControlFlow), RuntimeError> {
+    let mut indices = vec![];
+    for index_expr in index_exprs {
+        indices.push(ensure_integer(interp_expression(env, index_expr, writer)?)?);
+    }
+    let value = interp_expression(env, expr, writer)?;
+
+    let updated_value = assign_at_index(env.get(id)?, &indices, value)?;
+    env.reassign(id, updated_value)?;
+    Ok((Value::Nil, ControlFlow::Normal))
+}
+
+/// Interprets `then_statement` if `cond_expr` is truthy, and `else_statement_option` otherwise.
+fn interp_if(
+    env: &mut Environment,
+    cond_expr: &Expr,
+    then_statement: &Statement,
+    else_statement_option: Option<&Statement>,
+    in_loop: bool,
+    writer: &mut Writer
+) -> Result<(Value, ControlFlow), RuntimeError> {
+    if is_truthy(&interp_expression(env, cond_expr, writer)?) {
+        interp_statement(env, then_statement, in_loop, writer)
+    } else {
+        match else_statement_option {
+            Some(else_statement) => interp_statement(env, else_statement, in_loop, writer),
+            None => Ok((Value::Nil, ControlFlow::Normal)),
+        }
+    }
+}
+
+/// Interprets `while_block` until `cond_expr` is falsy, then interprets `else_block_option`
+/// unless the loop was left early.
+fn interp_while(
+    env: &mut Environment,
+    cond_expr: &Expr,
+    while_block: &Statement,
+    update_statement_option: Option<&Statement>,
+    else_block_option: Option<&Statement>,
+    in_loop: bool,
+    writer: &mut Writer
+) -> Result<(Value, ControlFlow), RuntimeError> {
+    Ok(loop {
+        if is_truthy(&interp_expression(env, cond_expr, writer)?) {
+            match interp_statement(env, while_block, true, writer)? {
+                (value, ControlFlow::Return) => break (value, ControlFlow::Return),
+                (_, ControlFlow::Break) => break (Value::Nil, ControlFlow::Normal),
+                (_, ControlFlow::Normal) => (),
+                (_, ControlFlow::Continue) => (),
+            };
+            if let Some(update_statement) = update_statement_option {
+                interp_statement(env, update_statement, true, writer)?;
             }
-            env.update_reassigned_entries(&block_env)?;
-            return Ok((block_value, ControlFlow::Normal));
+        } else {
+            // the else block only runs when the loop ends because its condition is false
+            break match else_block_option {
+                Some(else_block) => interp_statement(env, else_block, in_loop, writer)?,
+                None => (Value::Nil, ControlFlow::Normal),
+            };
         }
+    })
+}
+
+/// Interprets `statements` in a new scope. The value of the block is the value of its last
+/// statement, unless one of its statements returns, breaks, or continues.
+pub fn interp_block(
+    env: &mut Environment,
+    statements: &[Statement],
+    in_loop: bool,
+    writer: &mut Writer
+) -> Result<(Value, ControlFlow), RuntimeError> {
+    env.push_scope();
+    // the scope is left even if a statement fails, since the environment may still be used, such
+    // as by the next line of a REPL session
+    let result = interp_block_statements(env, statements, in_loop, writer);
+    env.pop_scope();
+    result
+}
+
+fn interp_block_statements(
+    env: &mut Environment,
+    statements: &[Statement],
+    in_loop: bool,
+    writer: &mut Writer
+) -> Result<(Value, ControlFlow), RuntimeError> {
+    let mut block_value = Value::Nil;
+    for statement in statements {
+        block_value = match interp_statement(env, statement, in_loop, writer)? {
+            (value, ControlFlow::Normal) => value,
+            (_, ControlFlow::Break) if !in_loop => return Err(BreakNotInLoop),
+            (_, ControlFlow::Continue) if !in_loop => return Err(ContinueNotInLoop),
+            // a return, or a break or continue in a loop, leaves the block
+            result => return Ok(result),
+        };
     }
+    Ok((block_value, ControlFlow::Normal))
 }
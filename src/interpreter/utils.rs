@@ -1,18 +1,46 @@
+use std::cmp::Ordering;
+
 use crate::{
     desugar::Expr,
+    environment::{Environment, IntWidth},
     error::RuntimeError::{self, *},
+    parser::{Arity, Builtin},
+    tokenizer::Operator,
 };
 
 use super::Value;
 
-pub fn ensure_single_arg(args: Vec<Expr>) -> Result<Expr, RuntimeError> {
-    if args.len() > 1 {
-        return Err(ArgMismatch("is_empty".to_string(), args.len(), 1));
+/// Ensures that exactly `N` arguments were passed to `builtin`, returning them as an array.
+pub fn ensure_args<const N: usize>(
+    builtin: Builtin,
+    args: Vec<Value>,
+) -> Result<[Value; N], RuntimeError> {
+    match <[Value; N]>::try_from(args) {
+        Ok(args) => Ok(args),
+        Err(args) => Err(ArgMismatch(builtin.to_string(), N, args.len())),
     }
+}
+
+/// Ensures that the number of arguments passed to `builtin` is allowed by its [Arity]. When there
+/// are too few arguments, the error names the fewest that are allowed, and when there are too many,
+/// it names the most that are allowed.
+pub fn ensure_arity(builtin: Builtin, args: &[Expr]) -> Result<(), RuntimeError> {
+    let (min, max_option) = match builtin.arity() {
+        Arity::Fixed(n) => (n, Some(n)),
+        Arity::Range(min, max) => (min, Some(max)),
+        Arity::Variadic(min) => (min, None),
+    };
+    match max_option {
+        _ if args.len() < min => Err(ArgMismatch(builtin.to_string(), min, args.len())),
+        Some(max) if args.len() > max => Err(ArgMismatch(builtin.to_string(), max, args.len())),
+        _ => Ok(()),
+    }
+}
 
-    match args.first() {
-        Some(arg) => Ok(arg.clone()),
-        None => return Err(ArgMismatch("is_empty".to_string(), 0, 1)),
+pub fn ensure_no_args(builtin: Builtin, args: &[Value]) -> Result<(), RuntimeError> {
+    match args.len() {
+        0 => Ok(()),
+        n => Err(ArgMismatch(builtin.to_string(), 0, n)),
     }
 }
 
@@ -22,3 +50,337 @@ pub fn ensure_list(value: Value) -> Result<Vec<Value>, RuntimeError> {
         bad_value => Err(ExpectedList(bad_value)),
     }
 }
+
+pub fn ensure_map(value: Value) -> Result<Vec<(Value, Value)>, RuntimeError> {
+    match value {
+        Value::Map(entries) => Ok(entries),
+        bad_value => Err(ExpectedMap(bad_value)),
+    }
+}
+
+/// Returns the value stored under `key` in the map made up of `entries`, if there is one.
+pub fn map_get(entries: &[(Value, Value)], key: &Value) -> Result<Option<Value>, RuntimeError> {
+    for (entry_key, entry_value) in entries {
+        if values_equal(entry_key.clone(), key.clone())? {
+            return Ok(Some(entry_value.clone()));
+        }
+    }
+    Ok(None)
+}
+
+/// Stores `value` under `key` in the map made up of `entries`. If the key is already in the map,
+/// it keeps its position and its value is replaced.
+pub fn map_insert(
+    entries: &mut Vec<(Value, Value)>,
+    key: Value,
+    value: Value,
+) -> Result<(), RuntimeError> {
+    for (entry_key, entry_value) in entries.iter_mut() {
+        if values_equal(entry_key.clone(), key.clone())? {
+            *entry_value = value;
+            return Ok(());
+        }
+    }
+    entries.push((key, value));
+    Ok(())
+}
+
+/// Compares two values for equality. An integer equals a float with no fractional part and the
+/// same value, but values of other differing types are never equal. Lists are equal if their
+/// elements are equal in order, and maps are equal if they have equal keys with equal values, in
+/// any order. Procedures cannot be compared and produce a [BadArgs] error.
+pub fn values_equal(left: Value, right: Value) -> Result<bool, RuntimeError> {
+    match (left, right) {
+        (Value::Num(num_left), Value::Num(num_right)) => Ok(num_left == num_right),
+        (Value::Float(num_left), Value::Float(num_right)) => Ok(num_left == num_right),
+        (Value::Num(num), Value::Float(float)) | (Value::Float(float), Value::Num(num)) => {
+            Ok(integer_equals_float(num, float))
+        }
+        (Value::Bool(bool_left), Value::Bool(bool_right)) => Ok(bool_left == bool_right),
+        (Value::Str(str_left), Value::Str(str_right)) => Ok(str_left == str_right),
+        (Value::Char(char_left), Value::Char(char_right)) => Ok(char_left == char_right),
+        // a char is equal to the string of only that char, such as the result of indexing a string
+        (Value::Str(str), Value::Char(char)) | (Value::Char(char), Value::Str(str)) => {
+            let mut chars = str.chars();
+            Ok(chars.next() == Some(char) && chars.next().is_none())
+        }
+        (Value::Nil, Value::Nil) => Ok(true),
+        (Value::List(list_left), Value::List(list_right)) => {
+            if list_left.len() != list_right.len() {
+                return Ok(false);
+            }
+            for (element_left, element_right) in list_left.into_iter().zip(list_right) {
+                if !values_equal(element_left, element_right)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        (Value::Map(entries_left), Value::Map(entries_right)) => {
+            // the keys of a map are distinct, so maps of the same size are equal if every entry of
+            // one is in the other
+            if entries_left.len() != entries_right.len() {
+                return Ok(false);
+            }
+            for (key, value_left) in entries_left {
+                let equal = match map_get(&entries_right, &key)? {
+                    Some(value_right) => values_equal(value_left, value_right)?,
+                    None => false,
+                };
+                if !equal {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        (v_left @ Value::Proc(..), v_right @ Value::Proc(..)) => {
+            Err(BadArgs(vec![v_left, v_right]))
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Returns whether `float` has no fractional part and is exactly equal to `num`.
+fn integer_equals_float(num: i64, float: f64) -> bool {
+    // i64::MIN is a power of two, so it converts to a float exactly, unlike i64::MAX
+    let in_range = float >= i64::MIN as f64 && float < -(i64::MIN as f64);
+    float.fract() == 0.0 && in_range && float as i64 == num
+}
+
+/// Converts a pair of numeric values to floats if at least one of them is a float. If both values
+/// are integers or either value is not a number, this function returns `None`.
+pub fn float_operands(left: &Value, right: &Value) -> Option<(f64, f64)> {
+    match (left, right) {
+        (Value::Float(num_left), Value::Float(num_right)) => Some((*num_left, *num_right)),
+        (Value::Float(num_left), Value::Num(num_right)) => Some((*num_left, *num_right as f64)),
+        (Value::Num(num_left), Value::Float(num_right)) => Some((*num_left as f64, *num_right)),
+        _ => None,
+    }
+}
+
+/// Converts a float with no fractional part to an integer. NaN is a [BadArg], and a float outside
+/// the range of an integer is an [IntegerOverflow].
+pub fn float_to_integer(float: f64) -> Result<i64, RuntimeError> {
+    if float.is_nan() {
+        return Err(BadArg(Value::Float(float)));
+    }
+    if float < i64::MIN as f64 || float >= -(i64::MIN as f64) {
+        return Err(IntegerOverflow);
+    }
+    Ok(float as i64)
+}
+
+/// Returns a [BadArg] error for whichever of `left` or `right` is not a number, preferring `left`.
+pub fn numeric_bad_arg(left: Value, right: Value) -> RuntimeError {
+    match left {
+        Value::Num(_) | Value::Float(_) => BadArg(right),
+        left => BadArg(left),
+    }
+}
+
+/// Compares two numbers or two strings with the relational operator `op`. Comparing a float with
+/// NaN is always false, and comparing `nil` is a [NilOperand] error.
+pub fn compare_values(op: Operator, left: Value, right: Value) -> Result<bool, RuntimeError> {
+    if matches!(left, Value::Nil) || matches!(right, Value::Nil) {
+        return Err(NilOperand(op));
+    }
+    let ordering = match (left, right) {
+        (Value::Num(num_left), Value::Num(num_right)) => num_left.partial_cmp(&num_right),
+        (Value::Str(str_left), Value::Str(str_right)) => str_left.partial_cmp(&str_right),
+        (Value::Str(_), v) => return Err(BadArg(v)),
+        (Value::Char(char_left), Value::Char(char_right)) => char_left.partial_cmp(&char_right),
+        (Value::Char(_), v) => return Err(BadArg(v)),
+        (v_left, v_right) => match float_operands(&v_left, &v_right) {
+            Some((num_left, num_right)) => num_left.partial_cmp(&num_right),
+            None => return Err(numeric_bad_arg(v_left, v_right)),
+        },
+    };
+
+    let Some(ordering) = ordering else {
+        return Ok(false);
+    };
+    Ok(match op {
+        Operator::LT => ordering.is_lt(),
+        Operator::GT => ordering.is_gt(),
+        Operator::LTE => ordering.is_le(),
+        Operator::GTE => ordering.is_ge(),
+        op => unreachable!("{op} is not a relational operator"),
+    })
+}
+
+/// Orders two numbers, two strings or two chars for sorting. A number and a string cannot be ordered, so
+/// they are a [BadArgs] error.
+pub fn default_order(left: &Value, right: &Value) -> Result<Ordering, RuntimeError> {
+    match (left, right) {
+        (Value::Num(num_left), Value::Num(num_right)) => Ok(num_left.cmp(num_right)),
+        (Value::Str(str_left), Value::Str(str_right)) => Ok(str_left.cmp(str_right)),
+        (Value::Str(_), Value::Num(_) | Value::Float(_))
+        | (Value::Num(_) | Value::Float(_), Value::Str(_)) => {
+            Err(BadArgs(vec![left.clone(), right.clone()]))
+        }
+        (Value::Str(_), v) => Err(BadArg(v.clone())),
+        (Value::Char(char_left), Value::Char(char_right)) => Ok(char_left.cmp(char_right)),
+        (Value::Char(_), v) => Err(BadArg(v.clone())),
+        // NaN is ordered after every other float, so that every list of numbers can be sorted
+        _ => match float_operands(left, right) {
+            Some((num_left, num_right)) => Ok(num_left.total_cmp(&num_right)),
+            None => Err(numeric_bad_arg(left.clone(), right.clone())),
+        },
+    }
+}
+
+/// Sorts `values` with a stable merge sort, ordering them by `compare`. Unlike the sorts of the
+/// standard library, this never panics when `compare` is not a total order, which a comparator
+/// written in Linger need not be.
+pub fn merge_sort(
+    mut values: Vec<Value>,
+    compare: &mut impl FnMut(&Value, &Value) -> Result<Ordering, RuntimeError>,
+) -> Result<Vec<Value>, RuntimeError> {
+    if values.len() <= 1 {
+        return Ok(values);
+    }
+    let right_values = values.split_off(values.len() / 2);
+    let mut left = merge_sort(values, compare)?.into_iter().peekable();
+    let mut right = merge_sort(right_values, compare)?.into_iter().peekable();
+
+    let mut merged = vec![];
+    while let (Some(left_value), Some(right_value)) = (left.peek(), right.peek()) {
+        // taking from the left unless it is strictly greater keeps the sort stable
+        let next = match compare(left_value, right_value)? {
+            Ordering::Greater => right.next(),
+            _ => left.next(),
+        };
+        merged.extend(next);
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+/// Returns a [BadArg] error for whichever of `left` or `right` is not an integer, preferring
+/// `left`.
+pub fn integer_bad_arg(left: Value, right: Value) -> RuntimeError {
+    match left {
+        Value::Num(_) => BadArg(right),
+        left => BadArg(left),
+    }
+}
+
+/// Returns `amount` as a number of bits to shift a 64-bit integer by, or an [InvalidShiftAmount]
+/// error if it is negative or at least 64.
+pub fn ensure_shift_amount(amount: i64) -> Result<u32, RuntimeError> {
+    match u32::try_from(amount) {
+        Ok(bits) if bits < i64::BITS => Ok(bits),
+        _ => Err(InvalidShiftAmount(amount)),
+    }
+}
+
+/// Returns the result of an integer operation, given its result with checked and with wrapping
+/// 64-bit arithmetic. With 32-bit integers the wrapping result is truncated, and with 64-bit
+/// integers an [IntegerOverflow] error is returned if the checked result overflowed.
+pub fn integer_result(
+    env: &Environment,
+    checked: Option<i64>,
+    wrapping: i64,
+) -> Result<Value, RuntimeError> {
+    match env.int_width() {
+        IntWidth::W32 => Ok(Value::Num(IntWidth::W32.wrap(wrapping))),
+        IntWidth::W64 => checked.map(Value::Num).ok_or(IntegerOverflow),
+    }
+}
+
+/// Raises `base` to the power of `exponent` with wrapping 64-bit arithmetic. Unlike
+/// [i64::wrapping_pow], the exponent may be larger than a u32.
+pub fn wrapping_pow(mut base: i64, mut exponent: u64) -> i64 {
+    let mut result: i64 = 1;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.wrapping_mul(base);
+        }
+        base = base.wrapping_mul(base);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Divides `left` by `right`, rounding the quotient toward negative infinity. Returns `None` if
+/// `right` is zero or the quotient does not fit in an i64.
+pub fn checked_floor_div(left: i64, right: i64) -> Option<i64> {
+    let quotient = left.checked_div(right)?;
+    // integer division truncates toward zero, which rounds up when the exact quotient is negative
+    if left % right != 0 && (left < 0) != (right < 0) {
+        Some(quotient - 1)
+    } else {
+        Some(quotient)
+    }
+}
+
+pub fn ensure_string(value: Value) -> Result<String, RuntimeError> {
+    match value {
+        Value::Str(s) => Ok(s),
+        bad_value => Err(BadArg(bad_value)),
+    }
+}
+
+pub fn ensure_integer(value: Value) -> Result<i64, RuntimeError> {
+    match value {
+        Value::Num(n) => Ok(n),
+        bad_value => Err(ExpectedInteger(bad_value)),
+    }
+}
+
+/// Returns whether `value` counts as true where a condition is expected. The falsy values are
+/// `false`, `nil`, `0`, `0.0`, the empty string, the empty list, and the empty map, and every
+/// other value is truthy.
+pub fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Nil => false,
+        Value::Num(n) => *n != 0,
+        Value::Float(n) => *n != 0.0,
+        Value::Str(s) => !s.is_empty(),
+        Value::List(list) => !list.is_empty(),
+        Value::Map(entries) => !entries.is_empty(),
+        Value::Char(_) | Value::Proc(..) => true,
+    }
+}
+
+/// Converts an integer or a float to a float, or returns a [BadArg] error for any other value.
+pub fn ensure_float(value: Value) -> Result<f64, RuntimeError> {
+    match value {
+        Value::Num(n) => Ok(n as f64),
+        Value::Float(n) => Ok(n),
+        bad_value => Err(BadArg(bad_value)),
+    }
+}
+
+/// Returns `container` with the element found by following `indices` through nested lists
+/// replaced by `value`.
+pub fn assign_at_index(
+    container: Value,
+    indices: &[i64],
+    value: Value,
+) -> Result<Value, RuntimeError> {
+    let (index, rest_indices) = match indices {
+        [index, rest_indices @ ..] => (*index, rest_indices),
+        [] => return Ok(value),
+    };
+
+    match container {
+        Value::List(mut list) => {
+            if index < 0 || index as usize >= list.len() {
+                return Err(IndexOutOfBounds(index));
+            }
+            let element = list[index as usize].clone();
+            list[index as usize] = assign_at_index(element, rest_indices, value)?;
+            Ok(Value::List(list))
+        }
+        _ => Err(InvalidAssignmentTarget),
+    }
+}
+
+/// Writes `message` to stderr as part of a trace, indented by the current call depth.
+pub fn trace(env: &Environment, message: String) {
+    let indent = env.call_depth() * 2;
+    eprintln!("[trace] {:indent$}{message}", "");
+}
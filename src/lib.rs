@@ -1,29 +1,56 @@
 use std::{fs::File, io::Write, path::Path};
 
-use interpreter::interp_program;
-use parser::parse_program;
+use error::LingerError;
+use interpreter::{interp_program, Value};
+use parser::{check_program, parse_program, Program};
 use tokenizer::tokenize;
 
 mod desugar;
 pub mod environment;
 pub mod error;
+pub mod fold;
 pub mod interpreter;
 pub mod parser;
+pub mod repl;
 pub mod tokenizer;
 
 
+/// The destination of a program's output. It must be [Send], since programs are interpreted on
+/// a thread of their own.
 pub struct Writer<'a> {
-    w: Box<dyn Write + 'a>,
+    w: Box<dyn Write + Send + 'a>,
 }
 
 impl<'a> Writer<'a> {
-    pub fn new(w: Box<dyn Write + 'a>) -> Self { Self { w } }
+    pub fn new(w: Box<dyn Write + Send + 'a>) -> Self { Self { w } }
+}
+
+/// Tokenizes, parses, and statically checks a linger program without executing it.
+pub fn parse_str(src: &str) -> Result<Program, LingerError> {
+    let tokens = tokenize(src)?;
+    let program = parse_program(tokens.as_slice())?;
+    check_program(&program)?;
+    Ok(program)
+}
+
+/// Parses a linger program and returns its desugared abstract syntax tree as a JSON string.
+pub fn ast_to_json(src: &str) -> Result<String, LingerError> {
+    let program = parse_str(src)?;
+    Ok(serde_json::to_string(&program).expect("a program should always be serializable to JSON"))
+}
+
+/// Executes a linger program, writing its output to stdout. On success, this function returns
+/// the return value of the main procedure.
+pub fn run_str(src: &str) -> Result<Value, LingerError> {
+    let program = parse_str(src)?;
+    let writer = &mut Writer::new(Box::new(std::io::stdout()));
+    Ok(interp_program(program, writer)?)
 }
 
 /// Executes a linger program. On success, this program returns the return value of the main
 /// procedure as a String. If there is an error in any step of the program (tokenization, parsing,
 /// or interpreting), this function will return that error as a [String].
-pub fn interp<'a>(s: String) -> Result<String, String> {
+pub fn interp(s: String) -> Result<String, String> {
     let tokens = match tokenize(s.as_str()) {
         Ok(tokens) => tokens,
         Err(e) => return Err(e.to_string()),
@@ -32,18 +59,21 @@ pub fn interp<'a>(s: String) -> Result<String, String> {
         Ok(program) => program,
         Err(e) => return Err(e.to_string()),
     };
+    if let Err(e) = check_program(&program) {
+        return Err(e.to_string());
+    }
 
     let writer = &mut Writer {
         w: Box::new(std::io::stdout()),
     };
 
-    return match interp_program(program, writer) {
+    match interp_program(program, writer) {
         Ok(value) => Ok(value.to_string()),
         Err(e) => Err(e.to_string()),
-    };
+    }
 }
 
-pub fn interp_to_file<'a>(s: String, path: &Path) -> Result<String, String> {
+pub fn interp_to_file(s: String, path: &Path) -> Result<String, String> {
     let tokens = match tokenize(s.as_str()) {
         Ok(tokens) => tokens,
         Err(e) => return Err(e.to_string()),
@@ -52,6 +82,9 @@ pub fn interp_to_file<'a>(s: String, path: &Path) -> Result<String, String> {
         Ok(program) => program,
         Err(e) => return Err(e.to_string()),
     };
+    if let Err(e) = check_program(&program) {
+        return Err(e.to_string());
+    }
 
     let file = match File::create(path) {
         Ok(file) => file,
@@ -60,13 +93,13 @@ pub fn interp_to_file<'a>(s: String, path: &Path) -> Result<String, String> {
 
     let writer = &mut Writer { w: Box::new(file) };
 
-    return match interp_program(program, writer) {
+    match interp_program(program, writer) {
         Ok(value) => Ok(value.to_string()),
         Err(e) => Err(e.to_string()),
-    };
+    }
 }
 
-pub fn interp_to_buffer<'a>(s: String, buf: &mut Vec<u8>) -> Result<String, String> {
+pub fn interp_to_buffer(s: String, buf: &mut Vec<u8>) -> Result<String, String> {
     let tokens = match tokenize(s.as_str()) {
         Ok(tokens) => tokens,
         Err(e) => return Err(e.to_string()),
@@ -75,11 +108,14 @@ pub fn interp_to_buffer<'a>(s: String, buf: &mut Vec<u8>) -> Result<String, Stri
         Ok(program) => program,
         Err(e) => return Err(e.to_string()),
     };
+    if let Err(e) = check_program(&program) {
+        return Err(e.to_string());
+    }
 
     let writer = &mut Writer { w: Box::new(buf) };
 
-    return match interp_program(program, writer) {
+    match interp_program(program, writer) {
         Ok(value) => Ok(value.to_string()),
         Err(e) => Err(e.to_string()),
-    };
+    }
 }
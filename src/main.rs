@@ -1,21 +1,100 @@
-use std::{env, fs, io::stdout, process::ExitCode};
+use std::{
+    env, fs,
+    io::{stdin, stdout},
+    process::ExitCode,
+    time::Instant,
+};
 
-use linger::{interpreter::interp_program, parser::parse_program, tokenizer::tokenize, Writer};
+use linger::{
+    environment::IntWidth,
+    error::source_context,
+    interpreter::{interp_program_with_options, InterpOptions, Value},
+    parser::{
+        check_program, desugar_program, find_missing_returns_in_program,
+        find_unused_bindings_in_program, parse_sugared_implicit_main, parse_sugared_program,
+    },
+    repl::run_repl,
+    tokenizer::tokenize,
+    Writer,
+};
+
+/// The flag which makes the binary print the desugared program instead of executing it.
+const DUMP_DESUGARED_FLAG: &str = "--dump-desugared";
+
+/// The flag which makes the interpreter trace each statement and procedure call to stderr.
+const TRACE_FLAG: &str = "--trace";
+
+/// The flag which makes the binary treat its argument as the statements of an implicit main
+/// procedure, rather than the path of a file containing a program.
+const EXPR_FLAG: &str = "--expr";
+
+/// The flag which makes the binary report how long each stage of running a program took to stderr.
+const TIME_FLAG: &str = "--time";
+
+/// The flag which makes the binary check that a program is valid without executing it.
+const CHECK_FLAG: &str = "--check";
+
+/// The flag which makes the binary warn about variables which are declared but never read.
+const WARN_UNUSED_FLAG: &str = "--warn-unused";
+
+/// The flag which makes the binary warn about procedures whose result is used, but which can
+/// finish without returning a value.
+const WARN_MISSING_RETURN_FLAG: &str = "--warn-missing-return";
+
+/// The prefix of the flag which sets the number of bits that integer arithmetic is performed
+/// with, either `--int-width=32` or `--int-width=64`.
+const INT_WIDTH_FLAG_PREFIX: &str = "--int-width=";
 
 fn main() -> ExitCode {
     let args: Vec<String> = env::args().collect();
+    // the flags of the interpreter come before the program, and every argument after the program
+    // is passed on to it unchanged, even if it looks like a flag
+    let flag_count = args
+        .iter()
+        .skip(1)
+        .take_while(|arg| is_interpreter_flag(arg))
+        .count();
+    let (flags, positional_args) = args[1..].split_at(flag_count);
+    let dump_desugared = flags.iter().any(|arg| arg == DUMP_DESUGARED_FLAG);
+    let trace = flags.iter().any(|arg| arg == TRACE_FLAG);
+    let expr_mode = flags.iter().any(|arg| arg == EXPR_FLAG);
+    let time = flags.iter().any(|arg| arg == TIME_FLAG);
+    let check_only = flags.iter().any(|arg| arg == CHECK_FLAG);
+    let warn_unused = flags.iter().any(|arg| arg == WARN_UNUSED_FLAG);
+    let warn_missing_return = flags.iter().any(|arg| arg == WARN_MISSING_RETURN_FLAG);
+    let int_width = match flags
+        .iter()
+        .filter_map(|arg| arg.strip_prefix(INT_WIDTH_FLAG_PREFIX))
+        .next_back()
+    {
+        None | Some("64") => IntWidth::W64,
+        Some("32") => IntWidth::W32,
+        Some(width) => {
+            eprintln!("invalid integer width {width}: expected 32 or 64");
+            return ExitCode::FAILURE;
+        }
+    };
+    let args: Vec<&String> = args.iter().take(1).chain(positional_args).collect();
     if args.len() < 2 {
-        eprintln!("usage: linger <FILE>");
-        return ExitCode::FAILURE;
+        return match run_repl(stdin().lock(), &mut Writer::new(Box::new(stdout()))) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
     }
 
-    let linger_file_name = args[1].as_str();
-
-    let linger_file_content = match fs::read_to_string(linger_file_name) {
-        Ok(content) => content,
-        Err(e) => {
-            eprintln!("error opening {linger_file_name}: {e}");
-            return ExitCode::FAILURE;
+    let linger_file_content = if expr_mode {
+        args[1].to_string()
+    } else {
+        let linger_file_name = args[1].as_str();
+        match fs::read_to_string(linger_file_name) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("error opening {linger_file_name}: {e}");
+                return ExitCode::FAILURE;
+            }
         }
     };
 
@@ -23,6 +102,7 @@ fn main() -> ExitCode {
     let debug_program = false;
     let debug_value = false;
 
+    let start = Instant::now();
     let tokens = match tokenize(linger_file_content.as_str()) {
         Ok(t) => t,
         Err(e) => {
@@ -30,24 +110,87 @@ fn main() -> ExitCode {
             return ExitCode::FAILURE;
         }
     };
+    report_time(time, "tokenize", start);
     if debug_tokens {
         dbg!(&tokens);
         return ExitCode::FAILURE;
     }
 
-    let program = match parse_program(tokens.as_slice()) {
+    let start = Instant::now();
+    let parse_result = if expr_mode {
+        parse_sugared_implicit_main(tokens.as_slice())
+    } else {
+        parse_sugared_program(tokens.as_slice())
+    };
+    let sugared_procedures = match parse_result {
+        Ok(procedures) => procedures,
+        Err(e) => {
+            eprintln!("{e}");
+            if let Some(context) = e
+                .position()
+                .and_then(|position| source_context(&linger_file_content, position))
+            {
+                eprintln!("{context}");
+            }
+            return ExitCode::FAILURE;
+        }
+    };
+    report_time(time, "parse", start);
+
+    let start = Instant::now();
+    let program = match desugar_program(sugared_procedures) {
         Ok(p) => p,
         Err(e) => {
             eprintln!("{e}");
             return ExitCode::FAILURE;
         }
     };
+    report_time(time, "desugar", start);
+
+    let start = Instant::now();
+    if let Err(e) = check_program(&program) {
+        eprintln!("{e}");
+        return ExitCode::FAILURE;
+    }
+    report_time(time, "check", start);
+    if warn_unused {
+        // warnings are reported without stopping the program from running
+        for unused_binding in find_unused_bindings_in_program(&program) {
+            eprintln!("{unused_binding}");
+            if let Some(context) = source_context(&linger_file_content, unused_binding.position) {
+                eprintln!("{context}");
+            }
+        }
+    }
+    if warn_missing_return {
+        for e in find_missing_returns_in_program(&program) {
+            eprintln!("warning: {e}");
+        }
+    }
     if debug_program {
         dbg!(&program);
         return ExitCode::FAILURE;
     }
+    if dump_desugared {
+        println!("{program}");
+        return ExitCode::SUCCESS;
+    }
+    if check_only {
+        return ExitCode::SUCCESS;
+    }
 
-    let value = match interp_program(program, &mut Writer::new(Box::new(stdout()))) {
+    // the arguments after the program are passed on to the program
+    let options = InterpOptions {
+        trace,
+        int_width,
+        args: args[2..].iter().map(|arg| arg.to_string()).collect(),
+        ..Default::default()
+    };
+    let start = Instant::now();
+    let interp_result =
+        interp_program_with_options(program, options, &mut Writer::new(Box::new(stdout())));
+    report_time(time, "interpret", start);
+    let value = match interp_result {
         Ok(v) => v,
         Err(e) => {
             eprintln!("{e}");
@@ -59,5 +202,35 @@ fn main() -> ExitCode {
         return ExitCode::SUCCESS;
     }
 
-    return ExitCode::SUCCESS;
+    // a numeric return value from main is used as the exit code of the process. A code which is
+    // too large is clamped, but a negative code is never reported as success
+    match value {
+        Value::Num(n) if n < 0 => ExitCode::FAILURE,
+        Value::Num(n) => ExitCode::from(n.min(255) as u8),
+        _ => ExitCode::SUCCESS,
+    }
+}
+
+/// Returns whether `arg` is one of the flags of the interpreter, rather than the program or an
+/// argument passed on to it.
+fn is_interpreter_flag(arg: &str) -> bool {
+    [
+        DUMP_DESUGARED_FLAG,
+        TRACE_FLAG,
+        EXPR_FLAG,
+        TIME_FLAG,
+        CHECK_FLAG,
+        WARN_UNUSED_FLAG,
+        WARN_MISSING_RETURN_FLAG,
+    ]
+    .contains(&arg)
+        || arg.starts_with(INT_WIDTH_FLAG_PREFIX)
+}
+
+/// Reports how long the stage of running a program called `stage`, which began at `start`, took
+/// to stderr if `time` is set.
+fn report_time(time: bool, stage: &str, start: Instant) {
+    if time {
+        eprintln!("[time] {stage}: {:?}", start.elapsed());
+    }
 }
@@ -1,71 +1,146 @@
-use crate::desugar::{desugar_statement, Procedure, Statement};
+use std::{collections::HashSet, fmt};
+
+use serde::Serialize;
+
+use crate::desugar::{
+    desugar_params, desugar_statement, desugar_statement_into_list, write_params, Param, Procedure,
+    Statement,
+};
 use crate::tokenizer::AssignOp;
 use crate::tokenizer::Operator;
+use crate::tokenizer::Position;
 use crate::{
     error::ParseError::{self, *},
-    tokenizer::Token as T,
+    tokenizer::{Token as T, TokenValue::SEMICOLON},
 };
 
-use self::procedures::parse_procs;
+use self::checks::{check_loop_control, check_procedure, Scopes};
+use self::procedures::{parse_proc, parse_procs};
+use self::statements::parse_statement;
 use self::utils::unexpected_token;
+use self::warnings::{always_gives_value, find_unused_bindings, find_used_results};
 
+mod checks;
 mod expressions;
 mod procedures;
 mod statements;
 mod utils;
+mod warnings;
+
+pub use self::warnings::UnusedBinding;
 
 /// A representation of a Linger program.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct Program {
     /// The top-level procedures of the program, excluding the main procedure.
     pub procedures: Vec<Procedure>,
+    /// The params of the main procedure of the program. A main procedure with a param is called
+    /// with the list of the program's command-line arguments.
+    pub main_params: Vec<Param>,
     /// The body of the main procedure of the program.
     pub main: Statement,
 }
 
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for procedure in &self.procedures {
+            writeln!(f, "{procedure}\n")?;
+        }
+        write!(f, "proc main")?;
+        write_params(f, &self.main_params, 0)?;
+        write!(f, " {}", self.main)
+    }
+}
+
+/// A single input to the REPL, which is either a top-level procedure definition or a statement.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub enum ReplInput {
+    Procedure(Procedure),
+    /// The desugared statements of a single input statement, which belong to the top-level scope.
+    Statements(Vec<Statement>),
+}
+
 /// A representation for a procedure in the Linger programming language.
 ///
 /// Structs beginning with the word "Sugared" mean that they are the part of
 /// the user-facing syntax of the language. These statements are later
 /// ["desugared"](https://en.wikipedia.org/wiki/Syntactic_sugar) (converted) to
 /// a subset of the language which is then executed.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct SugaredProcedure {
     pub name: String,
-    pub params: Vec<String>,
+    pub params: Vec<SugaredParam>,
     pub body: SugaredStatement,
 }
 
+/// A parameter of a procedure or lambda in the Linger programming language. If the parameter has
+/// a default value, then the argument for it may be omitted when the procedure is called. A
+/// variadic parameter, such as `...rest`, must be the last parameter and collects every extra
+/// argument into a list.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct SugaredParam {
+    pub name: String,
+    pub default: Option<SugaredExpr>,
+    pub variadic: bool,
+}
+
 /// A representation of a statement in the Linger programming language.
 ///
 /// Structs beginning with the word "Sugared" mean that they are the part of
 /// the user-facing syntax of the language. These statements are later
 /// ["desugared"](https://en.wikipedia.org/wiki/Syntactic_sugar) (converted) to
 /// a subset of the language which is then executed.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum SugaredStatement {
-    Expr(SugaredExpr),
-    Let(String, SugaredExpr),
-    Const(String, SugaredExpr),
-    Assign(String, SugaredExpr),
-    OperatorAssignment(AssignOp, String, SugaredExpr),
+    Expr(SugaredExpr, Position),
+    Let(String, SugaredExpr, Position),
+    Const(String, SugaredExpr, Position),
+    /// An initialization which binds each variable in the pattern to the element of a list at
+    /// the same position, such as `let [a, b] = [1, 2];`.
+    DestructuringLet(Vec<String>, SugaredExpr, Position),
+    Assign(String, SugaredExpr, Position),
+    IndexAssign(String, Vec<SugaredExpr>, SugaredExpr, Position),
+    OperatorAssignment(AssignOp, String, SugaredExpr, Position),
     Block(Vec<SugaredStatement>),
+    /// An if statement with a condition, a block, a list of else-if branches which each have the
+    /// position of their `else` keyword, and an optional else block.
     If(
         SugaredExpr,
         Box<SugaredStatement>,
-        Vec<(SugaredExpr, SugaredStatement)>,
+        Vec<(SugaredExpr, SugaredStatement, Position)>,
         Option<Box<SugaredStatement>>,
+        Position,
     ),
-    While(SugaredExpr, Box<SugaredStatement>),
+    /// A while loop with a condition, a body, and an optional else block which is run when the
+    /// loop ends without a `break`.
+    While(
+        SugaredExpr,
+        Box<SugaredStatement>,
+        Option<Box<SugaredStatement>>,
+        Position,
+    ),
+    /// A for loop with an initialization statement, a stop condition, an update statement, a
+    /// body, and an optional else block which is run when the loop ends without a `break`.
     For(
         Box<SugaredStatement>,
         SugaredExpr,
         Box<SugaredStatement>,
         Vec<SugaredStatement>,
+        Option<Box<SugaredStatement>>,
+        Position,
+    ),
+    /// A switch statement, made up of the expression being matched, the value and body of each
+    /// case, and an optional default body. Only the first matching case is executed; cases do not
+    /// fall through to the cases after them.
+    Switch(
+        SugaredExpr,
+        Vec<(SugaredExpr, SugaredStatement)>,
+        Option<Box<SugaredStatement>>,
+        Position,
     ),
     Break,
     Continue,
-    Return(Option<SugaredExpr>),
+    Return(Option<SugaredExpr>, Position),
 }
 
 /// A representation of an expression in the Linger programming language.
@@ -74,10 +149,11 @@ pub enum SugaredStatement {
 /// the user-facing syntax of the language. These statements are later
 /// ["desugared"](https://en.wikipedia.org/wiki/Syntactic_sugar) (converted) to
 /// a subset of the language which is then executed.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum SugaredExpr {
     Nil,
-    Num(f64),
+    Num(i64),
+    Float(f64),
     Bool(bool),
     Str(String),
     Var(String),
@@ -85,12 +161,25 @@ pub enum SugaredExpr {
     Unary(Operator, Box<SugaredExpr>),
     PrimitiveCall(Builtin, Vec<SugaredExpr>),
     Call(Box<SugaredExpr>, Vec<SugaredExpr>),
-    Lambda(Vec<String>, Box<SugaredStatement>),
+    Lambda(Vec<SugaredParam>, Box<SugaredStatement>),
     Index(Box<SugaredExpr>, Box<SugaredExpr>),
+    List(Vec<SugaredExpr>),
+    /// A chain of two or more relational comparisons, such as `a < b <= c`, which is true when
+    /// each operand compares to the next one as required. Each operand is evaluated at most once.
+    Comparison(Box<SugaredExpr>, Vec<(Operator, SugaredExpr)>),
+    /// A parenthesized, comma-separated sequence of expressions which are evaluated in order. The
+    /// value of the sequence is the value of its last expression.
+    Sequence(Vec<SugaredExpr>),
+    /// A block of statements in the position of an expression, such as `{ let x = 1; x + 1 }`,
+    /// whose value is the value of its last statement.
+    Block(Vec<SugaredStatement>),
+    /// An argument of a call, such as `...list`, which is expanded into one argument for each
+    /// element of the list. A spread can only occur in the arguments of a call.
+    Spread(Box<SugaredExpr>),
 }
 
 /// A built in procedure in the Linger programming language.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize)]
 pub enum Builtin {
     Print,
     List,
@@ -98,33 +187,338 @@ pub enum Builtin {
     IsNil,
     Head,
     Rest,
+    Len,
+    ReadLine,
+    ToNum,
+    ToStr,
+    TypeOf,
+    Assert,
+    Push,
+    Pop,
+    Concat,
+    MapOf,
+    HasKey,
+    Keys,
+    Values,
+    /// Checks that a list being destructured has as many elements as the pattern it is being
+    /// destructured into. This builtin can only be produced by desugaring.
+    DestructureLength,
+    Substr,
+    Split,
+    Join,
+    Abs,
+    Min,
+    Max,
+    FloorDiv,
+    Vars,
+    Exit,
+    Floor,
+    Ceil,
+    /// Rounds to the nearest integer, rounding halfway cases away from zero.
+    Round,
+    Upper,
+    Lower,
+    Trim,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Clamp,
+    Sign,
+    Map,
+    Filter,
+    Reduce,
+    Sort,
+    /// Produces the list of integers from a start up to, but not including, an end, counting by
+    /// an optional step which defaults to one.
+    Range,
+    Now,
+    Sleep,
+    Format,
+    /// Prints its arguments after the first, joined by the first argument instead of a space.
+    PrintSep,
+    /// Converts a codepoint to the char it encodes.
+    Chr,
+    /// Converts a char, or a string of exactly one char, to its codepoint.
+    Ord,
+    /// Converts a value to a bool by its truthiness, except that the strings "true" and "false"
+    /// are parsed, and any other string is an error.
+    ToBool,
+    /// Converts a value to a string written the way it would be in source code.
+    Repr,
+}
+
+impl fmt::Display for Builtin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Builtin::Print => write!(f, "print"),
+            Builtin::List => write!(f, "list"),
+            Builtin::IsEmpty => write!(f, "is_empty"),
+            Builtin::IsNil => write!(f, "is_nil"),
+            Builtin::Head => write!(f, "head"),
+            Builtin::Rest => write!(f, "rest"),
+            Builtin::Len => write!(f, "len"),
+            Builtin::ReadLine => write!(f, "read_line"),
+            Builtin::ToNum => write!(f, "to_num"),
+            Builtin::ToStr => write!(f, "to_str"),
+            Builtin::TypeOf => write!(f, "typeof"),
+            Builtin::Assert => write!(f, "assert"),
+            Builtin::Push => write!(f, "push"),
+            Builtin::Pop => write!(f, "pop"),
+            Builtin::Concat => write!(f, "concat"),
+            Builtin::MapOf => write!(f, "map_of"),
+            Builtin::HasKey => write!(f, "has_key"),
+            Builtin::Keys => write!(f, "keys"),
+            Builtin::Values => write!(f, "values"),
+            Builtin::DestructureLength => write!(f, "#destructure_length"),
+            Builtin::Substr => write!(f, "substr"),
+            Builtin::Split => write!(f, "split"),
+            Builtin::Join => write!(f, "join"),
+            Builtin::Abs => write!(f, "abs"),
+            Builtin::Min => write!(f, "min"),
+            Builtin::Max => write!(f, "max"),
+            Builtin::FloorDiv => write!(f, "floor_div"),
+            Builtin::Vars => write!(f, "vars"),
+            Builtin::Exit => write!(f, "exit"),
+            Builtin::Floor => write!(f, "floor"),
+            Builtin::Ceil => write!(f, "ceil"),
+            Builtin::Round => write!(f, "round"),
+            Builtin::Upper => write!(f, "upper"),
+            Builtin::Lower => write!(f, "lower"),
+            Builtin::Trim => write!(f, "trim"),
+            Builtin::Contains => write!(f, "contains"),
+            Builtin::StartsWith => write!(f, "starts_with"),
+            Builtin::EndsWith => write!(f, "ends_with"),
+            Builtin::Clamp => write!(f, "clamp"),
+            Builtin::Sign => write!(f, "sign"),
+            Builtin::Map => write!(f, "map"),
+            Builtin::Filter => write!(f, "filter"),
+            Builtin::Reduce => write!(f, "reduce"),
+            Builtin::Sort => write!(f, "sort"),
+            Builtin::Range => write!(f, "range"),
+            Builtin::Now => write!(f, "now"),
+            Builtin::Sleep => write!(f, "sleep"),
+            Builtin::Format => write!(f, "format"),
+            Builtin::PrintSep => write!(f, "print_sep"),
+            Builtin::Chr => write!(f, "chr"),
+            Builtin::Ord => write!(f, "ord"),
+            Builtin::ToBool => write!(f, "to_bool"),
+            Builtin::Repr => write!(f, "repr"),
+        }
+    }
+}
+
+/// The number of arguments that a builtin accepts.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Arity {
+    /// Exactly this many arguments.
+    Fixed(usize),
+    /// Between a minimum and a maximum number of arguments, inclusive.
+    Range(usize, usize),
+    /// At least this many arguments.
+    Variadic(usize),
+}
+
+impl Builtin {
+    /// Returns the number of arguments that this builtin accepts, which is checked before any of
+    /// the arguments of a call are evaluated.
+    pub fn arity(&self) -> Arity {
+        match self {
+            Builtin::ReadLine | Builtin::Vars | Builtin::Now => Arity::Fixed(0),
+            Builtin::IsEmpty
+            | Builtin::IsNil
+            | Builtin::Head
+            | Builtin::Rest
+            | Builtin::Len
+            | Builtin::ToNum
+            | Builtin::ToStr
+            | Builtin::TypeOf
+            | Builtin::Pop
+            | Builtin::Keys
+            | Builtin::Values
+            | Builtin::Abs
+            | Builtin::Exit
+            | Builtin::Floor
+            | Builtin::Ceil
+            | Builtin::Round
+            | Builtin::Upper
+            | Builtin::Lower
+            | Builtin::Trim
+            | Builtin::Sign
+            | Builtin::Sleep
+            | Builtin::Chr
+            | Builtin::Ord
+            | Builtin::ToBool
+            | Builtin::Repr => Arity::Fixed(1),
+            Builtin::Push
+            | Builtin::Concat
+            | Builtin::HasKey
+            | Builtin::DestructureLength
+            | Builtin::Split
+            | Builtin::Join
+            | Builtin::Min
+            | Builtin::Max
+            | Builtin::FloorDiv
+            | Builtin::Contains
+            | Builtin::StartsWith
+            | Builtin::EndsWith
+            | Builtin::Map
+            | Builtin::Filter => Arity::Fixed(2),
+            Builtin::Substr | Builtin::Clamp | Builtin::Reduce => Arity::Fixed(3),
+            Builtin::Assert | Builtin::Sort => Arity::Range(1, 2),
+            Builtin::Range => Arity::Range(2, 3),
+            // a map takes an even number of arguments, which is checked along with its keys
+            Builtin::Print | Builtin::List | Builtin::MapOf => Arity::Variadic(0),
+            Builtin::Format | Builtin::PrintSep => Arity::Variadic(1),
+        }
+    }
 }
 
 /// Parses a program from a list of tokens.
 pub fn parse_program(tokens: &[T]) -> Result<Program, ParseError> {
+    desugar_program(parse_sugared_program(tokens)?)
+}
+
+/// Parses a program from a list of tokens holding only statements, without any top-level
+/// procedures. The statements become the body of an implicit main procedure.
+pub fn parse_implicit_main_program(tokens: &[T]) -> Result<Program, ParseError> {
+    desugar_program(parse_sugared_implicit_main(tokens)?)
+}
+
+/// Parses the top-level procedures of a program from a list of tokens, without desugaring them.
+pub fn parse_sugared_program(tokens: &[T]) -> Result<Vec<SugaredProcedure>, ParseError> {
     let (procedures, rest) = parse_procs(tokens)?;
 
     if !rest.is_empty() {
         return Err(unexpected_token(rest)); // extra tokens
     }
 
-    let desugared_procs = procedures.iter().map(|proc| Procedure {
-        name: proc.name.to_string(),
-        params: proc.params.clone(),
-        body: desugar_statement(proc.body.clone()),
+    Ok(procedures)
+}
+
+/// Parses a list of tokens holding only statements into an implicit main procedure, without
+/// desugaring it.
+pub fn parse_sugared_implicit_main(tokens: &[T]) -> Result<Vec<SugaredProcedure>, ParseError> {
+    let mut statements = vec![];
+    let mut rest = tokens;
+    while !rest.is_empty() {
+        match parse_statement(rest, true)? {
+            (Some(statement), tokens) => {
+                statements.push(statement);
+                rest = tokens;
+            }
+            (None, _) => return Err(unexpected_token(rest)), // unmatched closing bracket
+        }
+    }
+
+    Ok(vec![SugaredProcedure {
+        name: "main".to_string(),
+        params: vec![],
+        body: SugaredStatement::Block(statements),
+    }])
+}
+
+/// Desugars the top-level procedures of a program. This function returns a [NoMain] error if none
+/// of the procedures is named `main`, and a [BreakNotInLoop] or [ContinueNotInLoop] error if a
+/// `break` or `continue` statement is not inside of a loop.
+pub fn desugar_program(procedures: Vec<SugaredProcedure>) -> Result<Program, ParseError> {
+    for proc in &procedures {
+        check_loop_control(&proc.params, &proc.body)?;
+    }
+
+    let desugared_procs = procedures.into_iter().map(|proc| Procedure {
+        name: proc.name,
+        params: desugar_params(proc.params),
+        body: desugar_statement(proc.body),
     });
 
-    let (main_procs, procs): (Vec<Procedure>, Vec<Procedure>) = desugared_procs
-        .into_iter()
-        .partition(|proc| proc.name == "main");
+    let (main_procs, procs): (Vec<Procedure>, Vec<Procedure>) =
+        desugared_procs.partition(|proc| proc.name == "main");
 
-    let main_proc = match main_procs.first() {
+    let main_proc = match main_procs.into_iter().next() {
         Some(proc) => proc,
         None => return Err(NoMain),
     };
 
-    return Ok(Program {
+    Ok(Program {
         procedures: procs,
-        main: main_proc.body.clone(),
-    });
+        main_params: main_proc.params,
+        main: main_proc.body,
+    })
+}
+
+/// Statically checks that every call of a procedure by name in `program` refers to a top-level
+/// procedure, or to a variable or parameter that is in scope at the call. If a call refers to
+/// anything else, this function returns an [UndefinedProcedure] error.
+pub fn check_program(program: &Program) -> Result<(), ParseError> {
+    let proc_names = program
+        .procedures
+        .iter()
+        .map(|proc| proc.name.to_string())
+        .collect();
+    let scopes = &mut Scopes::new(proc_names);
+
+    for proc in &program.procedures {
+        check_procedure(&proc.params, &proc.body, scopes)?;
+    }
+    check_procedure(&program.main_params, &program.main, scopes)
+}
+
+/// Returns the `let` and `const` bindings in each procedure of `program`, including the main
+/// procedure, whose variables are never read. Params are never reported, even if they are unused.
+pub fn find_unused_bindings_in_program(program: &Program) -> Vec<UnusedBinding> {
+    program
+        .procedures
+        .iter()
+        .flat_map(|proc| find_unused_bindings(&proc.params, &proc.body))
+        .chain(find_unused_bindings(&program.main_params, &program.main))
+        .collect()
+}
+
+/// Returns a [MissingReturn] error for each top-level procedure of `program` whose result is used
+/// somewhere in the program, but whose body can finish without reaching a `return` or a final
+/// expression statement. These errors are meant to be reported as warnings, since such a
+/// procedure returns `nil` when it finishes.
+pub fn find_missing_returns_in_program(program: &Program) -> Vec<ParseError> {
+    let mut used_names = HashSet::new();
+    for proc in &program.procedures {
+        find_used_results(&proc.params, &proc.body, &mut used_names);
+    }
+    find_used_results(&program.main_params, &program.main, &mut used_names);
+
+    program
+        .procedures
+        .iter()
+        .filter(|proc| used_names.contains(&proc.name) && !always_gives_value(&proc.body))
+        .map(|proc| MissingReturn(proc.name.to_string()))
+        .collect()
+}
+
+/// Parses a single REPL input from a list of tokens. The input is either a top-level procedure or
+/// a statement, in which case the trailing semicolon is optional.
+pub fn parse_repl_input(tokens: &[T]) -> Result<ReplInput, ParseError> {
+    if let (Some(proc), rest) = parse_proc(tokens)? {
+        if !rest.is_empty() {
+            return Err(unexpected_token(rest));
+        }
+        check_loop_control(&proc.params, &proc.body)?;
+
+        return Ok(ReplInput::Procedure(Procedure {
+            name: proc.name,
+            params: desugar_params(proc.params),
+            body: desugar_statement(proc.body),
+        }));
+    }
+
+    let (statement, rest) = match parse_statement(tokens, false)? {
+        (Some(statement), rest) => (statement, rest),
+        (None, _) => return Err(unexpected_token(tokens)),
+    };
+
+    match rest {
+        [] | [T(SEMICOLON, ..)] => {
+            check_loop_control(&[], &statement)?;
+            Ok(ReplInput::Statements(desugar_statement_into_list(statement)))
+        }
+        rest => Err(unexpected_token(rest)),
+    }
 }
@@ -0,0 +1,294 @@
+use std::collections::HashSet;
+
+use crate::{
+    desugar::{Expr, Param, Statement},
+    error::ParseError::{self, *},
+};
+
+use super::{SugaredExpr, SugaredParam, SugaredStatement};
+
+/// The names which are defined at some point in a program, innermost scope last.
+pub struct Scopes(Vec<HashSet<String>>);
+
+impl Scopes {
+    pub fn new(global_names: HashSet<String>) -> Self {
+        Self(vec![global_names])
+    }
+
+    fn declare(&mut self, name: &str) {
+        self.0
+            .last_mut()
+            .expect("there should always be a global scope")
+            .insert(name.to_string());
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|scope| scope.contains(name))
+    }
+
+    /// Runs `f` in a new innermost scope, which is discarded afterwards.
+    fn with_scope<F>(&mut self, f: F) -> Result<(), ParseError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), ParseError>,
+    {
+        self.0.push(HashSet::new());
+        let result = f(self);
+        self.0.pop();
+        result
+    }
+}
+
+/// Checks the body of a procedure with the given params, which are in scope for the whole body.
+/// Each default value may only refer to the params before it.
+pub fn check_procedure(
+    params: &[Param],
+    body: &Statement,
+    scopes: &mut Scopes,
+) -> Result<(), ParseError> {
+    scopes.with_scope(|scopes| {
+        for param in params {
+            if let Some(default_expr) = &param.default {
+                check_expr(default_expr, scopes)?;
+            }
+            scopes.declare(&param.name);
+        }
+        check_statement(body, scopes)
+    })
+}
+
+fn check_statement(statement: &Statement, scopes: &mut Scopes) -> Result<(), ParseError> {
+    match statement {
+        Statement::Expr(expr, _) => check_expr(expr, scopes),
+        Statement::Let(id, expr, _) | Statement::Const(id, expr, _) => {
+            check_expr(expr, scopes)?;
+            scopes.declare(id);
+            Ok(())
+        }
+        Statement::Assign(_, expr, _) => check_expr(expr, scopes),
+        Statement::IndexAssign(_, index_exprs, expr, _) => {
+            for index_expr in index_exprs {
+                check_expr(index_expr, scopes)?;
+            }
+            check_expr(expr, scopes)
+        }
+        Statement::If(cond_expr, then_statement, else_statement_option, _) => {
+            check_expr(cond_expr, scopes)?;
+            scopes.with_scope(|scopes| check_statement(then_statement, scopes))?;
+            match else_statement_option {
+                Some(else_statement) => {
+                    scopes.with_scope(|scopes| check_statement(else_statement, scopes))
+                }
+                None => Ok(()),
+            }
+        }
+        Statement::While(cond_expr, body, update_statement_option, else_statement_option, _) => {
+            check_expr(cond_expr, scopes)?;
+            scopes.with_scope(|scopes| check_statement(body, scopes))?;
+            if let Some(update_statement) = update_statement_option {
+                check_statement(update_statement, scopes)?;
+            }
+            match else_statement_option {
+                Some(else_statement) => {
+                    scopes.with_scope(|scopes| check_statement(else_statement, scopes))
+                }
+                None => Ok(()),
+            }
+        }
+        Statement::Block(statements) => scopes.with_scope(|scopes| {
+            for statement in statements {
+                check_statement(statement, scopes)?;
+            }
+            Ok(())
+        }),
+        Statement::Return(Some(expr), _) => check_expr(expr, scopes),
+        Statement::Return(None, _) | Statement::Break | Statement::Continue => Ok(()),
+    }
+}
+
+fn check_expr(expr: &Expr, scopes: &mut Scopes) -> Result<(), ParseError> {
+    match expr {
+        Expr::Nil | Expr::Num(_) | Expr::Float(_) | Expr::Bool(_) | Expr::Str(_) | Expr::Var(_) => {
+            Ok(())
+        }
+        Expr::Binary(_, left, right) | Expr::Index(left, right) => {
+            check_expr(left, scopes)?;
+            check_expr(right, scopes)
+        }
+        Expr::Unary(_, operand) | Expr::Spread(operand) => check_expr(operand, scopes),
+        Expr::Comparison(first, comparisons) => {
+            check_expr(first, scopes)?;
+            for (_, expr) in comparisons {
+                check_expr(expr, scopes)?;
+            }
+            Ok(())
+        }
+        Expr::PrimitiveCall(_, args) | Expr::Sequence(args) => check_exprs(args, scopes),
+        Expr::Call(proc_expr, args) => {
+            match proc_expr.as_ref() {
+                Expr::Var(name) if !scopes.contains(name) => {
+                    return Err(UndefinedProcedure(name.to_string()))
+                }
+                proc_expr => check_expr(proc_expr, scopes)?,
+            }
+            check_exprs(args, scopes)
+        }
+        Expr::Lambda(params, body) => check_procedure(params, body, scopes),
+        Expr::Block(statements) => scopes.with_scope(|scopes| {
+            for statement in statements {
+                check_statement(statement, scopes)?;
+            }
+            Ok(())
+        }),
+    }
+}
+
+fn check_exprs(exprs: &[Expr], scopes: &mut Scopes) -> Result<(), ParseError> {
+    for expr in exprs {
+        check_expr(expr, scopes)?;
+    }
+    Ok(())
+}
+
+/// Checks that every `break` and `continue` in the body of a procedure with the given params is
+/// inside of a loop. The body of a lambda is checked the same way, since a lambda cannot break out
+/// of a loop that it is created in. This function returns a [BreakNotInLoop] or
+/// [ContinueNotInLoop] error for the first one which is not.
+pub fn check_loop_control(
+    params: &[SugaredParam],
+    body: &SugaredStatement,
+) -> Result<(), ParseError> {
+    for param in params {
+        if let Some(default_expr) = &param.default {
+            check_loop_control_in_expr(default_expr, false)?;
+        }
+    }
+    check_loop_control_in_statement(body, false)
+}
+
+/// Checks the `break` and `continue` statements in `statement`, including those in its block
+/// expressions, where `in_loop` is whether `statement` is inside of a loop. The else block of a
+/// loop is run after the loop has finished, so it is only inside of a loop if the loop itself is.
+fn check_loop_control_in_statement(
+    statement: &SugaredStatement,
+    in_loop: bool,
+) -> Result<(), ParseError> {
+    match statement {
+        SugaredStatement::Break if !in_loop => Err(BreakNotInLoop),
+        SugaredStatement::Continue if !in_loop => Err(ContinueNotInLoop),
+        SugaredStatement::Break
+        | SugaredStatement::Continue
+        | SugaredStatement::Return(None, _) => Ok(()),
+        SugaredStatement::Expr(expr, _)
+        | SugaredStatement::Let(_, expr, _)
+        | SugaredStatement::Const(_, expr, _)
+        | SugaredStatement::DestructuringLet(_, expr, _)
+        | SugaredStatement::Assign(_, expr, _)
+        | SugaredStatement::OperatorAssignment(_, _, expr, _)
+        | SugaredStatement::Return(Some(expr), _) => check_loop_control_in_expr(expr, in_loop),
+        SugaredStatement::IndexAssign(_, index_exprs, expr, _) => {
+            check_loop_control_in_exprs(index_exprs, in_loop)?;
+            check_loop_control_in_expr(expr, in_loop)
+        }
+        SugaredStatement::Block(statements) => {
+            check_loop_control_in_statements(statements, in_loop)
+        }
+        SugaredStatement::If(cond_expr, then_block, else_ifs, else_block_option, _) => {
+            check_loop_control_in_expr(cond_expr, in_loop)?;
+            check_loop_control_in_statement(then_block, in_loop)?;
+            for (else_if_cond_expr, else_if_block, _) in else_ifs {
+                check_loop_control_in_expr(else_if_cond_expr, in_loop)?;
+                check_loop_control_in_statement(else_if_block, in_loop)?;
+            }
+            check_loop_control_in_else(else_block_option, in_loop)
+        }
+        SugaredStatement::While(cond_expr, body, else_block_option, _) => {
+            check_loop_control_in_expr(cond_expr, in_loop)?;
+            check_loop_control_in_statement(body, true)?;
+            check_loop_control_in_else(else_block_option, in_loop)
+        }
+        SugaredStatement::For(
+            init_statement,
+            cond_expr,
+            update_statement,
+            body_statements,
+            else_block_option,
+            _,
+        ) => {
+            check_loop_control_in_statement(init_statement, in_loop)?;
+            check_loop_control_in_expr(cond_expr, in_loop)?;
+            check_loop_control_in_statement(update_statement, in_loop)?;
+            check_loop_control_in_statements(body_statements, true)?;
+            check_loop_control_in_else(else_block_option, in_loop)
+        }
+        // a switch is not a loop, so a break in one of its cases leaves the enclosing loop
+        SugaredStatement::Switch(switch_expr, cases, default_block_option, _) => {
+            check_loop_control_in_expr(switch_expr, in_loop)?;
+            for (case_expr, case_block) in cases {
+                check_loop_control_in_expr(case_expr, in_loop)?;
+                check_loop_control_in_statement(case_block, in_loop)?;
+            }
+            check_loop_control_in_else(default_block_option, in_loop)
+        }
+    }
+}
+
+fn check_loop_control_in_statements(
+    statements: &[SugaredStatement],
+    in_loop: bool,
+) -> Result<(), ParseError> {
+    for statement in statements {
+        check_loop_control_in_statement(statement, in_loop)?;
+    }
+    Ok(())
+}
+
+fn check_loop_control_in_else(
+    else_block_option: &Option<Box<SugaredStatement>>,
+    in_loop: bool,
+) -> Result<(), ParseError> {
+    match else_block_option {
+        Some(else_block) => check_loop_control_in_statement(else_block, in_loop),
+        None => Ok(()),
+    }
+}
+
+fn check_loop_control_in_expr(expr: &SugaredExpr, in_loop: bool) -> Result<(), ParseError> {
+    match expr {
+        SugaredExpr::Nil
+        | SugaredExpr::Num(_)
+        | SugaredExpr::Float(_)
+        | SugaredExpr::Bool(_)
+        | SugaredExpr::Str(_)
+        | SugaredExpr::Var(_) => Ok(()),
+        SugaredExpr::Binary(_, left, right) | SugaredExpr::Index(left, right) => {
+            check_loop_control_in_expr(left, in_loop)?;
+            check_loop_control_in_expr(right, in_loop)
+        }
+        SugaredExpr::Unary(_, operand) | SugaredExpr::Spread(operand) => {
+            check_loop_control_in_expr(operand, in_loop)
+        }
+        SugaredExpr::Comparison(first, comparisons) => {
+            check_loop_control_in_expr(first, in_loop)?;
+            for (_, expr) in comparisons {
+                check_loop_control_in_expr(expr, in_loop)?;
+            }
+            Ok(())
+        }
+        SugaredExpr::PrimitiveCall(_, args)
+        | SugaredExpr::List(args)
+        | SugaredExpr::Sequence(args) => check_loop_control_in_exprs(args, in_loop),
+        SugaredExpr::Call(proc_expr, args) => {
+            check_loop_control_in_expr(proc_expr, in_loop)?;
+            check_loop_control_in_exprs(args, in_loop)
+        }
+        SugaredExpr::Lambda(params, body) => check_loop_control(params, body),
+        SugaredExpr::Block(statements) => check_loop_control_in_statements(statements, in_loop),
+    }
+}
+
+fn check_loop_control_in_exprs(exprs: &[SugaredExpr], in_loop: bool) -> Result<(), ParseError> {
+    for expr in exprs {
+        check_loop_control_in_expr(expr, in_loop)?;
+    }
+    Ok(())
+}
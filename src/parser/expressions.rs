@@ -1,73 +1,169 @@
 use crate::tokenizer::Operator::*;
 use crate::{
     error::ParseError::{self, *},
-    tokenizer::{Keyword::*, Token as T, TokenValue::*},
+    tokenizer::{
+        Keyword::*,
+        TemplatePart, Token as T,
+        TokenValue::{self, *},
+    },
 };
 
 use super::procedures::parse_params;
-use super::statements::parse_statement;
+use super::statements::{parse_statement, parse_statements};
 use super::utils::{
-    check_builtin, consume_token, match_operator, parse_binary_expr, unexpected_token,
+    binary_expression, check_builtin, consume_token, match_operator, parse_binary_expr,
+    parse_rest_binary_expr, unexpected_token,
 };
-use super::SugaredExpr;
+use super::{Builtin, SugaredExpr};
 
 pub fn parse_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
     parse_logical_or_expr(tokens)
 }
 
 pub fn parse_logical_or_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
-    return parse_binary_expr(parse_logical_and_expr, vec![LogicOr], tokens);
+    parse_binary_expr(parse_logical_and_expr, vec![LogicOr], tokens)
 }
 
 pub fn parse_logical_and_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
-    return parse_binary_expr(parse_equality_expr, vec![LogicAnd], tokens);
+    parse_binary_expr(parse_equality_expr, vec![LogicAnd], tokens)
 }
 
 pub fn parse_equality_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
-    return parse_binary_expr(parse_relational_expr, vec![Eq, Ne], tokens);
+    parse_binary_expr(parse_bit_or_expr, vec![Eq, Ne], tokens)
+}
+
+pub fn parse_bit_or_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
+    parse_binary_expr(parse_bit_xor_expr, vec![BitOr], tokens)
+}
+
+pub fn parse_bit_xor_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
+    parse_binary_expr(parse_bit_and_expr, vec![BitXor], tokens)
+}
+
+pub fn parse_bit_and_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
+    parse_binary_expr(parse_relational_expr, vec![BitAnd], tokens)
 }
 
 pub fn parse_relational_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
-    return parse_binary_expr(parse_additive_expr, vec![LT, GT, LTE, GTE], tokens);
+    let (first, mut tokens) = parse_shift_expr(tokens)?;
+    let mut comparisons = vec![];
+    while let Some((op, rest)) = match_operator(&[LT, GT, LTE, GTE], tokens) {
+        let (right, rest) = parse_shift_expr(rest)?;
+        comparisons.push((op, right));
+        tokens = rest;
+    }
+
+    // a chain of comparisons, such as `a < b < c`, compares each operand with the next one
+    let expr = match comparisons.len() {
+        0 => first,
+        1 => {
+            let (op, right) = comparisons.remove(0);
+            binary_expression(op, first, right)
+        }
+        _ => SugaredExpr::Comparison(Box::new(first), comparisons),
+    };
+    Ok((expr, tokens))
+}
+
+pub fn parse_shift_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
+    parse_binary_expr(parse_additive_expr, vec![ShiftLeft, ShiftRight], tokens)
 }
 
 pub fn parse_additive_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
-    return parse_binary_expr(parse_multiplicative_expr, vec![Plus, Minus], tokens);
+    let (mut expr, mut tokens) = parse_multiplicative_expr(tokens)?;
+    loop {
+        let (op, right, rest) = match tokens {
+            // only a variable can be decremented, so `--` after any other operand, as in `3--2`,
+            // subtracts a negated operand
+            [T(DOUBLE_MINUS, ..), rest @ ..] => {
+                let (operand, rest) = parse_unary_expr(rest)?;
+                let negated = SugaredExpr::Unary(Minus, Box::new(operand));
+                let (negated, rest) = parse_rest_exponent_expr(negated, rest)?;
+                let (right, rest) = parse_rest_multiplicative_expr(negated, rest)?;
+                (Minus, right, rest)
+            }
+            tokens => match match_operator(&[Plus, Minus], tokens) {
+                Some((op, rest)) => {
+                    let (right, rest) = parse_multiplicative_expr(rest)?;
+                    (op, right, rest)
+                }
+                None => return Ok((expr, tokens)),
+            },
+        };
+        expr = binary_expression(op, expr, right);
+        tokens = rest;
+    }
 }
 
 pub fn parse_multiplicative_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
-    return parse_binary_expr(parse_unary_expr, vec![Times, Mod, Div], tokens);
+    parse_binary_expr(parse_exponent_expr, vec![Times, Mod, Div], tokens)
+}
+
+fn parse_rest_multiplicative_expr(
+    left: SugaredExpr,
+    tokens: &[T],
+) -> Result<(SugaredExpr, &[T]), ParseError> {
+    parse_rest_binary_expr(parse_exponent_expr, vec![Times, Mod, Div], left, tokens)
+}
+
+pub fn parse_exponent_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
+    let (left, tokens) = parse_unary_expr(tokens)?;
+    parse_rest_exponent_expr(left, tokens)
+}
+
+fn parse_rest_exponent_expr(
+    left: SugaredExpr,
+    tokens: &[T],
+) -> Result<(SugaredExpr, &[T]), ParseError> {
+    match tokens {
+        // exponentiation is right-associative, so the right operand is parsed recursively
+        [T(OP(Pow), ..), tokens @ ..] => {
+            let (right, tokens) = parse_exponent_expr(tokens)?;
+            Ok((
+                SugaredExpr::Binary(Pow, Box::new(left), Box::new(right)),
+                tokens,
+            ))
+        }
+        tokens => Ok((left, tokens)),
+    }
 }
 
 pub fn parse_unary_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
     match match_operator(vec![Minus, LogicNot].as_slice(), tokens) {
         Some((operator, tokens)) => {
             let (right, tokens) = parse_unary_expr(tokens)?;
-            return Ok((SugaredExpr::Unary(operator, Box::new(right)), tokens));
+            Ok((SugaredExpr::Unary(operator, Box::new(right)), tokens))
         }
         None => {
             let (increment_op_option, tokens) = match tokens {
+                // a decrement can only target a variable, so `--` before anything else is a
+                // double negation
+                [T(DOUBLE_MINUS, ..), rest @ ..] if !matches!(rest, [T(ID(_), ..), ..]) => {
+                    let (right, tokens) = parse_unary_expr(rest)?;
+                    let negated = SugaredExpr::Unary(Minus, Box::new(right));
+                    return Ok((SugaredExpr::Unary(Minus, Box::new(negated)), tokens));
+                }
                 [T(DOUBLE_PLUS, ..), tokens @ ..] => (Some(PreIncrement), tokens),
                 [T(DOUBLE_MINUS, ..), tokens @ ..] => (Some(PreDecrement), tokens),
                 tokens => (None, tokens),
             };
             let (terminal_expr, tokens) = parse_call_or_index_expr(tokens)?;
             match increment_op_option {
-                Some(op) => return Ok((SugaredExpr::Unary(op, Box::new(terminal_expr)), tokens)),
+                Some(op) => Ok((SugaredExpr::Unary(op, Box::new(terminal_expr)), tokens)),
                 None => match tokens {
-                    [T(DOUBLE_PLUS, ..), tokens @ ..] => {
-                        return Ok((
-                            SugaredExpr::Unary(PostIncrement, Box::new(terminal_expr)),
-                            tokens,
-                        ))
-                    }
-                    [T(DOUBLE_MINUS, ..), tokens @ ..] => {
-                        return Ok((
+                    [T(DOUBLE_PLUS, ..), tokens @ ..] => Ok((
+                        SugaredExpr::Unary(PostIncrement, Box::new(terminal_expr)),
+                        tokens,
+                    )),
+                    [T(DOUBLE_MINUS, ..), tokens @ ..]
+                        if matches!(terminal_expr, SugaredExpr::Var(_)) =>
+                    {
+                        Ok((
                             SugaredExpr::Unary(PostDecrement, Box::new(terminal_expr)),
                             tokens,
                         ))
                     }
-                    tokens => return Ok((terminal_expr, tokens)),
+                    tokens => Ok((terminal_expr, tokens)),
                 },
             }
         }
@@ -81,6 +177,13 @@ pub fn parse_call_or_index_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), Par
             [T(LPAREN, ..), rest @ ..] => {
                 let (args, rest) = parse_args(rest)?;
                 let call_expr = match check_builtin(&expr) {
+                    // builtins check their arguments before evaluating them, so an argument
+                    // list of unknown length cannot be spread into one
+                    Some(builtin)
+                        if args.iter().any(|arg| matches!(arg, SugaredExpr::Spread(_))) =>
+                    {
+                        return Err(SpreadIntoBuiltin(builtin.to_string()))
+                    }
                     Some(builtin) => SugaredExpr::PrimitiveCall(builtin, args),
                     None => SugaredExpr::Call(Box::new(expr), args),
                 };
@@ -95,62 +198,148 @@ pub fn parse_call_or_index_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), Par
             _ => break,
         }
     }
-    return Ok((expr, tokens));
+    Ok((expr, tokens))
 }
 
 pub fn parse_terminal_expr(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
     match tokens {
         [T(STR(s), ..), tokens @ ..] => Ok((SugaredExpr::Str(s.to_string()), tokens)),
+        [T(TEMPLATE(parts), ..), tokens @ ..] => Ok((parse_template(parts)?, tokens)),
+        // there are no map literals, so a bracket in the position of an expression always begins
+        // a block
+        [T(L_CURLY_BRACKET, ..), tokens @ ..] => {
+            let (statements, tokens) = parse_statements(tokens)?;
+            Ok((SugaredExpr::Block(statements), tokens))
+        }
         [T(KW(True), ..), tokens @ ..] => Ok((SugaredExpr::Bool(true), tokens)),
         [T(KW(False), ..), tokens @ ..] => Ok((SugaredExpr::Bool(false), tokens)),
         [T(KW(Nil), ..), tokens @ ..] => Ok((SugaredExpr::Nil, tokens)),
         [T(KW(kw), ..), ..] => Err(KeywordAsVar(kw.to_string())),
         [T(ID(id), ..), tokens @ ..] => Ok((SugaredExpr::Var(id.to_string()), tokens)),
         [T(LPAREN, ..), tokens @ ..] => match parse_params(tokens) {
-            // if the next sequence of tokens is a params list, then parse a lambda expression
-            Ok((params, tokens)) => {
-                let tokens = consume_token(THIN_ARROW, tokens)?;
+            // if the next sequence of tokens is a params list followed by an arrow, then parse a
+            // lambda expression
+            Ok((params, [T(THIN_ARROW, ..), tokens @ ..])) => {
                 let (lambda_body, tokens) = match parse_statement(tokens, false)? {
                     (Some(statement), tokens) => (statement, tokens),
                     _ => return Err(ExpectedStatement),
                 };
-                return Ok((SugaredExpr::Lambda(params, Box::new(lambda_body)), tokens));
-            }
-            // if the next sequence of tokens is a valid sequence of tokens, but not a params list,
-            // then parse a parenthesized expression
-            Err(UnexpectedToken(_)) => {
-                let (expr, tokens) = parse_expr(tokens)?;
-                let tokens = consume_token(RPAREN, tokens)?;
-                return Ok((expr, tokens));
+                Ok((SugaredExpr::Lambda(params, Box::new(lambda_body)), tokens))
             }
+            // if the next sequence of tokens is a valid sequence of tokens, but not a params list
+            // followed by an arrow, then parse a parenthesized expression or sequence
+            Ok(_) | Err(UnexpectedToken(_)) => parse_parenthesized_exprs(tokens),
+            // a keyword such as `true` or `nil` can begin an expression, so a keyword in the
+            // params list is only reported if the tokens are not a parenthesized expression either
+            Err(e @ KeywordAsParam(_)) => parse_parenthesized_exprs(tokens).map_err(|_| e),
             // if the next sequence of tokens is not a valid sequence of tokens, return the error
-            Err(e) => return Err(e),
+            Err(e) => Err(e),
         },
 
+        [T(L_SQUARE_BRACKET, ..), tokens @ ..] => {
+            let (elements, tokens) = parse_list_elements(tokens)?;
+            Ok((SugaredExpr::List(elements), tokens))
+        }
         [T(NUM(n), ..), tokens @ ..] => Ok((SugaredExpr::Num(*n), tokens)),
+        [T(FLOAT(n), ..), tokens @ ..] => Ok((SugaredExpr::Float(*n), tokens)),
         tokens => Err(unexpected_token(tokens)),
     }
 }
 
+/// Parses the comma-separated expressions following an opening parenthesis, consuming the closing
+/// parenthesis. A single expression is returned as is, while several expressions form a
+/// [SugaredExpr::Sequence].
+fn parse_parenthesized_exprs(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
+    let (expr, mut tokens) = parse_expr(tokens)?;
+    let mut exprs = vec![expr];
+    while let [T(COMMA, ..), rest @ ..] = tokens {
+        let (expr, rest) = parse_expr(rest)?;
+        exprs.push(expr);
+        tokens = rest;
+    }
+    let tokens = consume_token(RPAREN, tokens)?;
+
+    if exprs.len() == 1 {
+        return Ok((exprs.remove(0), tokens));
+    }
+    Ok((SugaredExpr::Sequence(exprs), tokens))
+}
+
 pub fn parse_args(tokens: &[T]) -> Result<(Vec<SugaredExpr>, &[T]), ParseError> {
+    parse_expr_list(RPAREN, parse_arg, tokens)
+}
+
+/// Parses a single argument of a call, which is either an expression or a spread of an expression,
+/// such as `...list`.
+fn parse_arg(tokens: &[T]) -> Result<(SugaredExpr, &[T]), ParseError> {
     match tokens {
-        [T(RPAREN, ..), tokens @ ..] => Ok((vec![], tokens)),
-        tokens => {
+        [T(ELLIPSIS, ..), tokens @ ..] => {
             let (expr, tokens) = parse_expr(tokens)?;
-            let (mut rest_args, tokens) = parse_rest_args(tokens)?;
+            Ok((SugaredExpr::Spread(Box::new(expr)), tokens))
+        }
+        tokens => parse_expr(tokens),
+    }
+}
+
+pub fn parse_list_elements(tokens: &[T]) -> Result<(Vec<SugaredExpr>, &[T]), ParseError> {
+    parse_expr_list(R_SQUARE_BRACKET, parse_expr, tokens)
+}
+
+/// Type alias for a function which parses a single element of an expression list.
+type ElementParser = fn(&[T]) -> Result<(SugaredExpr, &[T]), ParseError>;
+
+/// Parses a comma-separated list of elements, each parsed by `parse_element`, terminated by a
+/// token with a [TokenValue] of `end`, consuming the terminating token. The last element may be
+/// followed by a comma.
+pub fn parse_expr_list(
+    end: TokenValue,
+    parse_element: ElementParser,
+    tokens: &[T],
+) -> Result<(Vec<SugaredExpr>, &[T]), ParseError> {
+    match tokens {
+        [T(value, ..), tokens @ ..] if *value == end => Ok((vec![], tokens)),
+        tokens => {
+            let (expr, tokens) = parse_element(tokens)?;
+            let (mut rest_exprs, tokens) = parse_rest_expr_list(end, parse_element, tokens)?;
 
             let mut vec = vec![expr];
-            vec.append(&mut rest_args);
-            return Ok((vec, tokens));
+            vec.append(&mut rest_exprs);
+            Ok((vec, tokens))
         }
     }
 }
 
-pub fn parse_rest_args(tokens: &[T]) -> Result<(Vec<SugaredExpr>, &[T]), ParseError> {
+pub fn parse_rest_expr_list(
+    end: TokenValue,
+    parse_element: ElementParser,
+    tokens: &[T],
+) -> Result<(Vec<SugaredExpr>, &[T]), ParseError> {
     match tokens {
-        [T(RPAREN, ..), tokens @ ..] => Ok((vec![], tokens)),
-        [T(COMMA, ..), T(RPAREN, ..), ..] => Err(unexpected_token(tokens)),
-        [T(COMMA, ..), tokens @ ..] => parse_args(tokens),
+        [T(value, ..), tokens @ ..] if *value == end => Ok((vec![], tokens)),
+        [T(COMMA, ..), T(value, ..), tokens @ ..] if *value == end => Ok((vec![], tokens)),
+        [T(COMMA, ..), tokens @ ..] => parse_expr_list(end, parse_element, tokens),
         tokens => Err(unexpected_token(tokens)),
     }
 }
+
+/// Parses a template literal into the concatenation of its parts, where the value of each embedded
+/// expression is converted to a string with `to_str`.
+fn parse_template(parts: &[TemplatePart]) -> Result<SugaredExpr, ParseError> {
+    let mut part_exprs = vec![];
+    for part in parts {
+        part_exprs.push(match part {
+            TemplatePart::Str(s) => SugaredExpr::Str(s.to_string()),
+            TemplatePart::Expr(expr_tokens) => {
+                let (expr, rest) = parse_expr(expr_tokens)?;
+                if !rest.is_empty() {
+                    return Err(unexpected_token(rest));
+                }
+                SugaredExpr::PrimitiveCall(Builtin::ToStr, vec![expr])
+            }
+        });
+    }
+    Ok(part_exprs
+        .into_iter()
+        .reduce(|acc, part_expr| binary_expression(Plus, acc, part_expr))
+        .unwrap_or(SugaredExpr::Str(String::new())))
+}
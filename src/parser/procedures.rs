@@ -1,34 +1,33 @@
+use std::collections::HashSet;
+
 use crate::{
     error::ParseError::{self, *},
     tokenizer::{Keyword::*, Token as T, TokenValue::*},
 };
 
-use super::utils::{ensure_block, unexpected_token};
+use super::expressions::parse_expr;
 use super::statements::parse_statement;
-use super::SugaredProcedure;
+use super::utils::{ensure_block, unexpected_token};
+use super::{SugaredParam, SugaredProcedure};
 
 pub fn parse_procs(tokens: &[T]) -> Result<(Vec<SugaredProcedure>, &[T]), ParseError> {
-    let (proc_option, tokens) = parse_proc(tokens)?;
-
-    match proc_option {
-        Some(proc) => {
-            let (mut rest_procs, tokens) = parse_procs(tokens)?;
-
-            if rest_procs
-                .clone()
-                .iter()
-                .find(|p| p.name == proc.name)
-                .is_some()
-            {
-                return Err(MultipleSameNamedProcs(proc.name.to_string()));
-            }
+    let mut procs = vec![];
+    let mut tokens = tokens;
+    while let (Some(proc), rest) = parse_proc(tokens)? {
+        procs.push(proc);
+        tokens = rest;
+    }
 
-            let mut vec = vec![proc];
-            vec.append(&mut rest_procs);
-            return Ok((vec, tokens));
+    // the procs are checked from last to first, so that the reported name is the duplicate of
+    // the latest proc which has one
+    let mut later_names = HashSet::new();
+    for proc in procs.iter().rev() {
+        if !later_names.insert(proc.name.as_str()) {
+            return Err(MultipleSameNamedProcs(proc.name.to_string()));
         }
-        None => Ok((vec![], tokens)),
     }
+
+    Ok((procs, tokens))
 }
 
 pub fn parse_proc(tokens: &[T]) -> Result<(Option<SugaredProcedure>, &[T]), ParseError> {
@@ -53,13 +52,46 @@ pub fn parse_proc(tokens: &[T]) -> Result<(Option<SugaredProcedure>, &[T]), Pars
     }
 }
 
-pub fn parse_params(tokens: &[T]) -> Result<(Vec<String>, &[T]), ParseError> {
+pub fn parse_params(tokens: &[T]) -> Result<(Vec<SugaredParam>, &[T]), ParseError> {
     match tokens {
         [T(RPAREN, ..), rest @ ..] => Ok((vec![], rest)),
         [T(KW(kw), ..), ..] => Err(KeywordAsParam(kw.to_string())),
+        [T(ELLIPSIS, ..), T(KW(kw), ..), ..] => Err(KeywordAsParam(kw.to_string())),
+        // a variadic param must be the last param
+        [T(ELLIPSIS, ..), T(ID(param_name), ..), T(RPAREN, ..), rest_toks @ ..]
+        | [T(ELLIPSIS, ..), T(ID(param_name), ..), T(COMMA, ..), T(RPAREN, ..), rest_toks @ ..] => Ok((
+            vec![SugaredParam {
+                name: param_name.to_string(),
+                default: None,
+                variadic: true,
+            }],
+            rest_toks,
+        )),
+        [T(ELLIPSIS, ..), T(ID(_), ..), rest_toks @ ..] => Err(unexpected_token(rest_toks)),
+        [T(ID(param_name), ..), T(ASSIGN, ..), rest_toks @ ..] => {
+            let (default_expr, rest_toks) = parse_expr(rest_toks)?;
+            let (mut rest_params, rest_toks) = parse_rest_params(rest_toks)?;
+            if let Some(required_param) = rest_params
+                .iter()
+                .find(|p| p.default.is_none() && !p.variadic)
+            {
+                return Err(RequiredParamAfterDefault(required_param.name.to_string()));
+            }
+            let mut params = vec![SugaredParam {
+                name: param_name.to_string(),
+                default: Some(default_expr),
+                variadic: false,
+            }];
+            params.append(&mut rest_params);
+            Ok((params, rest_toks))
+        }
         [T(ID(param_name), ..), rest_toks @ ..] => {
             let (mut rest_params, rest_toks) = parse_rest_params(rest_toks)?;
-            let mut params = vec![param_name.to_string()];
+            let mut params = vec![SugaredParam {
+                name: param_name.to_string(),
+                default: None,
+                variadic: false,
+            }];
             params.append(&mut rest_params);
             Ok((params, rest_toks))
         }
@@ -67,10 +99,10 @@ pub fn parse_params(tokens: &[T]) -> Result<(Vec<String>, &[T]), ParseError> {
     }
 }
 
-pub fn parse_rest_params(tokens: &[T]) -> Result<(Vec<String>, &[T]), ParseError> {
+pub fn parse_rest_params(tokens: &[T]) -> Result<(Vec<SugaredParam>, &[T]), ParseError> {
     match tokens {
         [T(RPAREN, ..), tokens @ ..] => Ok((vec![], tokens)),
-        [T(COMMA, ..), T(RPAREN, ..), ..] => Err(unexpected_token(tokens)),
+        [T(COMMA, ..), T(RPAREN, ..), tokens @ ..] => Ok((vec![], tokens)),
         [T(COMMA, ..), tokens @ ..] => parse_params(tokens),
         tokens => Err(unexpected_token(tokens)),
     }
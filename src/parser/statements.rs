@@ -6,24 +6,24 @@ use crate::{
 use super::{
     expressions::parse_expr,
     utils::{
-        conditionally_consume_semicolon, consume_token, ensure_block, is_assignment,
-        is_assignment_or_initialization,
+        conditionally_consume_semicolon, consume_token, ensure_block, index_assignment_target,
+        is_assignment, is_assignment_or_initialization, unexpected_token,
     },
     SugaredStatement,
 };
 
 pub fn parse_statements(tokens: &[T]) -> Result<(Vec<SugaredStatement>, &[T]), ParseError> {
-    let (statement_option, tokens) = parse_statement(tokens, true)?;
-
-    let statement = match statement_option {
-        Some(statement) => statement,
-        None => return Ok((vec![], tokens)),
-    };
-
-    let (mut rest_statements, tokens) = parse_statements(tokens)?;
-    let mut vec = vec![statement];
-    vec.append(&mut rest_statements);
-    Ok((vec, tokens))
+    let mut statements = vec![];
+    let mut tokens = tokens;
+    loop {
+        match parse_statement(tokens, true)? {
+            (Some(statement), rest) => {
+                statements.push(statement);
+                tokens = rest;
+            }
+            (None, rest) => return Ok((statements, rest)),
+        }
+    }
 }
 
 pub fn parse_statement(
@@ -34,38 +34,68 @@ pub fn parse_statement(
         [T(R_CURLY_BRACKET, ..), tokens @ ..] => Ok((None, tokens)),
         [T(KW(Let), ..), T(KW(kw), ..), ..] => Err(KeywordAsVar(kw.to_string())),
         [T(KW(Const), ..), T(KW(kw), ..), ..] => Err(KeywordAsVar(kw.to_string())),
-        [T(KW(Let), ..), T(ID(var_name), ..), T(ASSIGN, ..), tokens @ ..] => {
+        [T(KW(Let), line, col), T(L_SQUARE_BRACKET, ..), tokens @ ..] => {
+            let (var_names, tokens) = parse_destructuring_pattern(tokens)?;
+            let tokens = consume_token(ASSIGN, tokens)?;
+            let (list_expr, tokens) = parse_expr(tokens)?;
+
+            let tokens = conditionally_consume_semicolon(tokens, parse_semicolon)?;
+
+            Ok((
+                Some(SugaredStatement::DestructuringLet(
+                    var_names,
+                    list_expr,
+                    (*line, *col),
+                )),
+                tokens,
+            ))
+        }
+        [T(KW(Let), line, col), T(ID(var_name), ..), T(ASSIGN, ..), tokens @ ..] => {
             let (var_expr, tokens) = parse_expr(tokens)?;
 
             let tokens = conditionally_consume_semicolon(tokens, parse_semicolon)?;
 
             Ok((
-                Some(SugaredStatement::Let(var_name.to_string(), var_expr)),
+                Some(SugaredStatement::Let(
+                    var_name.to_string(),
+                    var_expr,
+                    (*line, *col),
+                )),
                 tokens,
             ))
         }
-        [T(KW(Const), ..), T(ID(var_name), ..), T(ASSIGN, ..), tokens @ ..] => {
+        [T(KW(Const), line, col), T(ID(var_name), ..), T(ASSIGN, ..), tokens @ ..] => {
             let (var_expr, tokens) = parse_expr(tokens)?;
 
             let tokens = conditionally_consume_semicolon(tokens, parse_semicolon)?;
 
             Ok((
-                Some(SugaredStatement::Const(var_name.to_string(), var_expr)),
+                Some(SugaredStatement::Const(
+                    var_name.to_string(),
+                    var_expr,
+                    (*line, *col),
+                )),
                 tokens,
             ))
         }
         [T(KW(kw), ..), T(ASSIGN, ..), ..] => Err(KeywordAsVar(kw.to_string())),
-        [T(ID(var_name), ..), T(ASSIGN, ..), tokens @ ..] => {
+        [T(ID(var_name), line, col), T(ASSIGN, ..), tokens @ ..] => {
             let (var_expr, tokens) = parse_expr(tokens)?;
 
             let tokens = conditionally_consume_semicolon(tokens, parse_semicolon)?;
 
             Ok((
-                Some(SugaredStatement::Assign(var_name.to_string(), var_expr)),
+                Some(SugaredStatement::Assign(
+                    var_name.to_string(),
+                    var_expr,
+                    (*line, *col),
+                )),
                 tokens,
             ))
         }
-        [T(ID(var_name), ..), T(ASSIGN_OP(assign_op), ..), tokens @ ..] => {
+        // an operator followed by `=` that is not an operator assignment, such as `<<=`
+        [T(ID(_), ..), T(OP(op), ..), T(ASSIGN, ..), ..] => Err(UnsupportedAssignOp(*op)),
+        [T(ID(var_name), line, col), T(ASSIGN_OP(assign_op), ..), tokens @ ..] => {
             let (var_expr, tokens) = parse_expr(tokens)?;
 
             let tokens = conditionally_consume_semicolon(tokens, parse_semicolon)?;
@@ -75,29 +105,27 @@ pub fn parse_statement(
                     *assign_op,
                     var_name.to_string(),
                     var_expr,
+                    (*line, *col),
                 )),
                 tokens,
             ))
         }
-        [T(KW(If), ..), T(LPAREN, ..), tokens @ ..] => {
+        [T(KW(If), line, col), T(LPAREN, ..), tokens @ ..] => {
             let (cond_expr, tokens) = parse_expr(tokens)?;
             let tokens = consume_token(RPAREN, tokens)?;
             let (then_block_option, mut tokens) = parse_statement(tokens, true)?;
             let then_block = ensure_block(then_block_option)?;
 
             let mut else_ifs = vec![];
-            loop {
-                match tokens {
-                    [T(KW(Else), ..), T(KW(If), ..), T(LPAREN, ..), rest @ ..] => {
-                        let (else_if_cond, rest) = parse_expr(rest)?;
-                        let rest = consume_token(RPAREN, rest)?;
-                        let (else_if_block_option, rest) = parse_statement(rest, true)?;
-                        let else_if_block = ensure_block(else_if_block_option)?;
-                        else_ifs.push((else_if_cond, else_if_block));
-                        tokens = rest;
-                    }
-                    _ => break,
-                }
+            while let [T(KW(Else), else_line, else_col), T(KW(If), ..), T(LPAREN, ..), rest @ ..] =
+                tokens
+            {
+                let (else_if_cond, rest) = parse_expr(rest)?;
+                let rest = consume_token(RPAREN, rest)?;
+                let (else_if_block_option, rest) = parse_statement(rest, true)?;
+                let else_if_block = ensure_block(else_if_block_option)?;
+                else_ifs.push((else_if_cond, else_if_block, (*else_line, *else_col)));
+                tokens = rest;
             }
 
             let (else_block_option, tokens) = match tokens {
@@ -115,25 +143,29 @@ pub fn parse_statement(
                     Box::new(then_block),
                     else_ifs,
                     else_block_option,
+                    (*line, *col),
                 )),
                 tokens,
             ))
         }
-        [T(KW(While), ..), T(LPAREN, ..), tokens @ ..] => {
+        [T(KW(While), line, col), T(LPAREN, ..), tokens @ ..] => {
             let (while_cond_expr, tokens) = parse_expr(tokens)?;
             let tokens = consume_token(RPAREN, tokens)?;
             let (while_block_option, tokens) = parse_statement(tokens, true)?;
             let while_block = ensure_block(while_block_option)?;
+            let (else_block_option, tokens) = parse_loop_else(tokens)?;
 
             Ok((
                 Some(SugaredStatement::While(
                     while_cond_expr,
                     Box::new(while_block),
+                    else_block_option,
+                    (*line, *col),
                 )),
                 tokens,
             ))
         }
-        [T(KW(For), ..), T(LPAREN, ..), tokens @ ..] => {
+        [T(KW(For), line, col), T(LPAREN, ..), tokens @ ..] => {
             let (var_statement_option, tokens) = parse_statement(tokens, true)?;
             let var_statement = match var_statement_option {
                 Some(statement) => {
@@ -164,30 +196,70 @@ pub fn parse_statement(
 
             let (for_block_option, tokens) = parse_statement(tokens, true)?;
             let for_block_statements = match for_block_option {
-                Some(statement) => match statement {
-                    SugaredStatement::Block(statements) => statements,
-                    _ => return Err(ExpectedBlock),
-                },
-                None => return Err(ExpectedBlock),
+                Some(SugaredStatement::Block(statements)) => statements,
+                _ => return Err(ExpectedBlock),
             };
+            let (else_block_option, tokens) = parse_loop_else(tokens)?;
 
-            return Ok((
+            Ok((
                 Some(SugaredStatement::For(
                     Box::new(var_statement),
                     stop_cond_expr,
                     Box::new(reassign_statement),
                     for_block_statements,
+                    else_block_option,
+                    (*line, *col),
                 )),
                 tokens,
-            ));
+            ))
         }
-        [T(KW(Return), ..), T(SEMICOLON, ..), tokens @ ..] => {
-            Ok((Some(SugaredStatement::Return(None)), tokens))
+        [T(KW(Switch), line, col), T(LPAREN, ..), tokens @ ..] => {
+            let (switch_expr, tokens) = parse_expr(tokens)?;
+            let tokens = consume_token(RPAREN, tokens)?;
+            let mut tokens = consume_token(L_CURLY_BRACKET, tokens)?;
+
+            let mut cases = vec![];
+            while let [T(KW(Case), ..), rest @ ..] = tokens {
+                let (case_expr, rest) = parse_expr(rest)?;
+                let rest = consume_token(COLON, rest)?;
+                let (case_statements, rest) = parse_case_statements(rest)?;
+                cases.push((case_expr, SugaredStatement::Block(case_statements)));
+                tokens = rest;
+            }
+
+            let (default_block_option, tokens) = match tokens {
+                [T(KW(Default), ..), tokens @ ..] => {
+                    let tokens = consume_token(COLON, tokens)?;
+                    let (default_statements, tokens) = parse_case_statements(tokens)?;
+                    (
+                        Some(Box::new(SugaredStatement::Block(default_statements))),
+                        tokens,
+                    )
+                }
+                tokens => (None, tokens),
+            };
+            let tokens = consume_token(R_CURLY_BRACKET, tokens)?;
+
+            Ok((
+                Some(SugaredStatement::Switch(
+                    switch_expr,
+                    cases,
+                    default_block_option,
+                    (*line, *col),
+                )),
+                tokens,
+            ))
+        }
+        [T(KW(Return), line, col), T(SEMICOLON, ..), tokens @ ..] => {
+            Ok((Some(SugaredStatement::Return(None, (*line, *col))), tokens))
         }
-        [T(KW(Return), ..), tokens @ ..] => {
+        [T(KW(Return), line, col), tokens @ ..] => {
             let (return_expr, tokens) = parse_expr(tokens)?;
             let tokens = consume_token(SEMICOLON, tokens)?;
-            Ok((Some(SugaredStatement::Return(Some(return_expr))), tokens))
+            Ok((
+                Some(SugaredStatement::Return(Some(return_expr), (*line, *col))),
+                tokens,
+            ))
         }
         [T(KW(Break), ..), tokens @ ..] => {
             let tokens = consume_token(SEMICOLON, tokens)?;
@@ -201,11 +273,89 @@ pub fn parse_statement(
             let (statements, tokens) = parse_statements(tokens)?;
             Ok((Some(SugaredStatement::Block(statements)), tokens))
         }
-        tokens => match parse_expr(tokens)? {
+        [T(_, line, col), ..] => match parse_expr(tokens)? {
+            (expr, [T(ASSIGN, ..), rest @ ..]) if index_assignment_target(&expr).is_some() => {
+                let (var_name, index_exprs) = index_assignment_target(&expr)
+                    .expect("the guard should ensure that the expression is an index target");
+                let (value_expr, tokens) = parse_expr(rest)?;
+
+                let tokens = conditionally_consume_semicolon(tokens, parse_semicolon)?;
+
+                Ok((
+                    Some(SugaredStatement::IndexAssign(
+                        var_name,
+                        index_exprs,
+                        value_expr,
+                        (*line, *col),
+                    )),
+                    tokens,
+                ))
+            }
+            // the last expression of a block may omit its semicolon, since its value is the value
+            // of the block
+            (expr, tokens @ [T(R_CURLY_BRACKET, ..), ..]) => {
+                Ok((Some(SugaredStatement::Expr(expr, (*line, *col))), tokens))
+            }
             (expr, tokens) => {
                 let tokens = conditionally_consume_semicolon(tokens, parse_semicolon)?;
-                Ok((Some(SugaredStatement::Expr(expr)), tokens))
+                Ok((Some(SugaredStatement::Expr(expr, (*line, *col))), tokens))
             }
         },
+        [] => Err(UnexpectedEOF),
+    }
+}
+
+/// Parses the statements in the body of a switch case, which ends at the next `case` or `default`
+/// label or at the closing bracket of the switch statement. The ending token is not consumed.
+fn parse_case_statements(tokens: &[T]) -> Result<(Vec<SugaredStatement>, &[T]), ParseError> {
+    let mut statements = vec![];
+    let mut tokens = tokens;
+    loop {
+        match tokens {
+            [T(KW(Case) | KW(Default) | R_CURLY_BRACKET, ..), ..] => {
+                return Ok((statements, tokens))
+            }
+            rest => {
+                let (statement_option, rest) = parse_statement(rest, true)?;
+                statements.push(statement_option.ok_or(ExpectedStatement)?);
+                tokens = rest;
+            }
+        }
+    }
+}
+
+/// Parses the optional else block after the body of a loop.
+fn parse_loop_else(tokens: &[T]) -> Result<(Option<Box<SugaredStatement>>, &[T]), ParseError> {
+    match tokens {
+        [T(KW(Else), ..), tokens @ ..] => {
+            let (else_block_option, tokens) = parse_statement(tokens, true)?;
+            let else_block = ensure_block(else_block_option)?;
+            Ok((Some(Box::new(else_block)), tokens))
+        }
+        tokens => Ok((None, tokens)),
+    }
+}
+
+/// Parses the comma-separated variable names of a destructuring pattern, up to and including the
+/// closing square bracket.
+fn parse_destructuring_pattern(tokens: &[T]) -> Result<(Vec<String>, &[T]), ParseError> {
+    let mut var_names = vec![];
+    let mut tokens = tokens;
+    loop {
+        tokens = match tokens {
+            [T(R_SQUARE_BRACKET, ..), tokens @ ..] if var_names.is_empty() => {
+                return Ok((var_names, tokens))
+            }
+            [T(KW(kw), ..), ..] => return Err(KeywordAsVar(kw.to_string())),
+            [T(ID(var_name), ..), tokens @ ..] => {
+                var_names.push(var_name.to_string());
+                match tokens {
+                    [T(COMMA, ..), tokens @ ..] => tokens,
+                    [T(R_SQUARE_BRACKET, ..), tokens @ ..] => return Ok((var_names, tokens)),
+                    tokens => return Err(unexpected_token(tokens)),
+                }
+            }
+            tokens => return Err(unexpected_token(tokens)),
+        }
     }
 }
@@ -29,6 +29,52 @@ pub fn check_builtin(expr: &SugaredExpr) -> Option<Builtin> {
             "is_nil" => Some(Builtin::IsNil),
             "head" => Some(Builtin::Head),
             "rest" => Some(Builtin::Rest),
+            "len" => Some(Builtin::Len),
+            "read_line" => Some(Builtin::ReadLine),
+            "to_num" => Some(Builtin::ToNum),
+            "to_str" => Some(Builtin::ToStr),
+            "typeof" => Some(Builtin::TypeOf),
+            "assert" => Some(Builtin::Assert),
+            "push" => Some(Builtin::Push),
+            "pop" => Some(Builtin::Pop),
+            "concat" => Some(Builtin::Concat),
+            "map_of" => Some(Builtin::MapOf),
+            "has_key" => Some(Builtin::HasKey),
+            "keys" => Some(Builtin::Keys),
+            "values" => Some(Builtin::Values),
+            "substr" => Some(Builtin::Substr),
+            "split" => Some(Builtin::Split),
+            "join" => Some(Builtin::Join),
+            "abs" => Some(Builtin::Abs),
+            "min" => Some(Builtin::Min),
+            "max" => Some(Builtin::Max),
+            "floor_div" => Some(Builtin::FloorDiv),
+            "vars" => Some(Builtin::Vars),
+            "exit" => Some(Builtin::Exit),
+            "floor" => Some(Builtin::Floor),
+            "ceil" => Some(Builtin::Ceil),
+            "round" => Some(Builtin::Round),
+            "upper" => Some(Builtin::Upper),
+            "lower" => Some(Builtin::Lower),
+            "trim" => Some(Builtin::Trim),
+            "contains" => Some(Builtin::Contains),
+            "starts_with" => Some(Builtin::StartsWith),
+            "ends_with" => Some(Builtin::EndsWith),
+            "clamp" => Some(Builtin::Clamp),
+            "sign" => Some(Builtin::Sign),
+            "map" => Some(Builtin::Map),
+            "filter" => Some(Builtin::Filter),
+            "reduce" => Some(Builtin::Reduce),
+            "sort" => Some(Builtin::Sort),
+            "range" => Some(Builtin::Range),
+            "now" => Some(Builtin::Now),
+            "sleep" => Some(Builtin::Sleep),
+            "format" => Some(Builtin::Format),
+            "print_sep" => Some(Builtin::PrintSep),
+            "chr" => Some(Builtin::Chr),
+            "ord" => Some(Builtin::Ord),
+            "to_bool" => Some(Builtin::ToBool),
+            "repr" => Some(Builtin::Repr),
             _ => None,
         },
         _ => None,
@@ -55,9 +101,9 @@ pub fn conditionally_consume_semicolon(
     should_consume: bool,
 ) -> Result<&[T], ParseError> {
     if should_consume {
-        return consume_token(SEMICOLON, tokens);
+        consume_token(SEMICOLON, tokens)
     } else {
-        return Ok(tokens);
+        Ok(tokens)
     }
 }
 
@@ -67,16 +113,7 @@ pub fn conditionally_consume_semicolon(
 /// then this function returns `None`.
 pub fn match_operator<'a>(operators: &[Operator], tokens: &'a [T]) -> Option<(Operator, &'a [T])> {
     match tokens {
-        [T(value, ..), rest @ ..] => match value {
-            OP(b) => {
-                if operators.contains(b) {
-                    return Some((*b, rest));
-                } else {
-                    return None;
-                }
-            }
-            _ => None,
-        },
+        [T(OP(b), ..), rest @ ..] if operators.contains(b) => Some((*b, rest)),
         _ => None,
     }
 }
@@ -90,7 +127,19 @@ pub fn parse_binary_expr(
     operators: Vec<Operator>,
     tokens: &[T],
 ) -> Result<(SugaredExpr, &[T]), ParseError> {
-    let (mut expr, mut tokens) = parse_expr(tokens)?;
+    let (first, tokens) = parse_expr(tokens)?;
+    parse_rest_binary_expr(parse_expr, operators, first, tokens)
+}
+
+/// Like [parse_binary_expr], but for a binary expression whose first operand has already been
+/// parsed.
+pub fn parse_rest_binary_expr(
+    parse_expr: BinaryExpressionParser,
+    operators: Vec<Operator>,
+    first: SugaredExpr,
+    tokens: &[T],
+) -> Result<(SugaredExpr, &[T]), ParseError> {
+    let (mut expr, mut tokens) = (first, tokens);
     loop {
         match match_operator(operators.as_slice(), tokens) {
             Some((op, rest)) => {
@@ -127,24 +176,39 @@ pub fn ensure_block(
     }
 }
 
+/// If `expr` is a chain of one or more [Index Expressions](SugaredExpr::Index) applied to a
+/// variable, such as `a[0][1]`, this function returns the variable name and the index expressions
+/// in order. Otherwise, this function returns `None`.
+pub fn index_assignment_target(expr: &SugaredExpr) -> Option<(String, Vec<SugaredExpr>)> {
+    match expr {
+        SugaredExpr::Index(indexable_expr, index_expr) => match indexable_expr.as_ref() {
+            SugaredExpr::Var(var_name) => Some((var_name.to_string(), vec![*index_expr.clone()])),
+            indexable_expr => {
+                let (var_name, mut index_exprs) = index_assignment_target(indexable_expr)?;
+                index_exprs.push(*index_expr.clone());
+                Some((var_name, index_exprs))
+            }
+        },
+        _ => None,
+    }
+}
+
 pub fn is_assignment(statement: &SugaredStatement) -> bool {
     match statement {
-        SugaredStatement::Assign(_, _) => true,
-        SugaredStatement::OperatorAssignment(_, _, _) => true,
-        SugaredStatement::Expr(expr) => match expr {
-            SugaredExpr::Unary(op, _) => match op {
-                PreIncrement | PostIncrement | PreDecrement | PostDecrement => true,
-                _ => false,
-            },
-            _ => false,
-        },
+        SugaredStatement::Assign(..) => true,
+        SugaredStatement::IndexAssign(..) => true,
+        SugaredStatement::OperatorAssignment(..) => true,
+        SugaredStatement::Expr(SugaredExpr::Unary(op, _), _) => matches!(
+            op,
+            PreIncrement | PostIncrement | PreDecrement | PostDecrement
+        ),
         _ => false,
     }
 }
 
 pub fn is_assignment_or_initialization(statement: &SugaredStatement) -> bool {
     match statement {
-        SugaredStatement::Let(_, _) => true,
+        SugaredStatement::Let(..) => true,
         statement => is_assignment(statement),
     }
 }
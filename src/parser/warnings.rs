@@ -0,0 +1,354 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    desugar::{Expr, Param, Statement},
+    tokenizer::Position,
+};
+
+/// A `let` or `const` binding whose variable is never read after it is declared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnusedBinding {
+    pub name: String,
+    pub position: Position,
+}
+
+impl fmt::Display for UnusedBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (line, col) = self.position;
+        write!(
+            f,
+            "warning: unused variable {} @ ({line}, {col})",
+            self.name
+        )
+    }
+}
+
+/// A variable declared in a procedure. Params have no position, since they are never reported.
+struct Binding {
+    name: String,
+    position: Option<Position>,
+    used: bool,
+}
+
+/// The bindings declared at some point in a procedure, innermost scope last, along with the
+/// unused bindings of the scopes which have already ended.
+struct Bindings {
+    scopes: Vec<Vec<Binding>>,
+    unused: Vec<UnusedBinding>,
+}
+
+impl Bindings {
+    fn declare(&mut self, name: &str, position: Option<Position>) {
+        self.scopes
+            .last_mut()
+            .expect("a binding should only be declared inside a scope")
+            .push(Binding {
+                name: name.to_string(),
+                position,
+                used: false,
+            });
+    }
+
+    /// Marks the innermost binding of `name` as used. A name which is not bound in the procedure
+    /// refers to a top-level procedure or a global, which are never reported.
+    fn read(&mut self, name: &str) {
+        let binding_option = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|binding| binding.name == name);
+        if let Some(binding) = binding_option {
+            binding.used = true;
+        }
+    }
+
+    /// Runs `f` in a new innermost scope, then records the bindings of that scope which were
+    /// never read. The names introduced by desugaring are not identifiers, and are never reported.
+    fn with_scope<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.scopes.push(vec![]);
+        f(self);
+        let scope = self
+            .scopes
+            .pop()
+            .expect("the scope should not have been removed");
+        self.unused.extend(
+            scope
+                .into_iter()
+                .filter_map(|binding| match binding.position {
+                    Some(position)
+                        if !binding.used
+                            && binding.name.starts_with(|c: char| c.is_ascii_alphabetic()) =>
+                    {
+                        Some(UnusedBinding {
+                            name: binding.name,
+                            position,
+                        })
+                    }
+                    _ => None,
+                }),
+        );
+    }
+}
+
+/// Returns the `let` and `const` bindings in the body of a procedure with the given params which
+/// are never read, in the order that they are declared. A binding which is shadowed before it is
+/// read is unused, even if the variable that shadows it is read.
+pub fn find_unused_bindings(params: &[Param], body: &Statement) -> Vec<UnusedBinding> {
+    let mut bindings = Bindings {
+        scopes: vec![],
+        unused: vec![],
+    };
+    visit_procedure(params, body, &mut bindings);
+    bindings.unused.sort_by_key(|binding| binding.position);
+    bindings.unused
+}
+
+fn visit_procedure(params: &[Param], body: &Statement, bindings: &mut Bindings) {
+    bindings.with_scope(|bindings| {
+        for param in params {
+            if let Some(default_expr) = &param.default {
+                visit_expr(default_expr, bindings);
+            }
+            bindings.declare(&param.name, None);
+        }
+        visit_statement(body, bindings);
+    });
+}
+
+fn visit_statement(statement: &Statement, bindings: &mut Bindings) {
+    match statement {
+        Statement::Expr(expr, _) => visit_expr(expr, bindings),
+        Statement::Let(id, expr, position) | Statement::Const(id, expr, position) => {
+            visit_expr(expr, bindings);
+            bindings.declare(id, Some(*position));
+        }
+        // assigning to a variable does not read it
+        Statement::Assign(_, expr, _) => visit_expr(expr, bindings),
+        Statement::IndexAssign(_, index_exprs, expr, _) => {
+            visit_exprs(index_exprs, bindings);
+            visit_expr(expr, bindings);
+        }
+        Statement::If(cond_expr, then_statement, else_statement_option, _) => {
+            visit_expr(cond_expr, bindings);
+            bindings.with_scope(|bindings| visit_statement(then_statement, bindings));
+            if let Some(else_statement) = else_statement_option {
+                bindings.with_scope(|bindings| visit_statement(else_statement, bindings));
+            }
+        }
+        Statement::While(cond_expr, body, update_statement_option, else_statement_option, _) => {
+            visit_expr(cond_expr, bindings);
+            bindings.with_scope(|bindings| visit_statement(body, bindings));
+            if let Some(update_statement) = update_statement_option {
+                visit_statement(update_statement, bindings);
+            }
+            if let Some(else_statement) = else_statement_option {
+                bindings.with_scope(|bindings| visit_statement(else_statement, bindings));
+            }
+        }
+        Statement::Block(statements) => bindings.with_scope(|bindings| {
+            for statement in statements {
+                visit_statement(statement, bindings);
+            }
+        }),
+        Statement::Return(Some(expr), _) => visit_expr(expr, bindings),
+        Statement::Return(None, _) | Statement::Break | Statement::Continue => {}
+    }
+}
+
+fn visit_expr(expr: &Expr, bindings: &mut Bindings) {
+    match expr {
+        Expr::Nil | Expr::Num(_) | Expr::Float(_) | Expr::Bool(_) | Expr::Str(_) => {}
+        Expr::Var(name) => bindings.read(name),
+        Expr::Binary(_, left, right) | Expr::Index(left, right) => {
+            visit_expr(left, bindings);
+            visit_expr(right, bindings);
+        }
+        Expr::Unary(_, operand) | Expr::Spread(operand) => visit_expr(operand, bindings),
+        Expr::Comparison(first, comparisons) => {
+            visit_expr(first, bindings);
+            for (_, expr) in comparisons {
+                visit_expr(expr, bindings);
+            }
+        }
+        Expr::PrimitiveCall(_, args) | Expr::Sequence(args) => visit_exprs(args, bindings),
+        Expr::Call(proc_expr, args) => {
+            visit_expr(proc_expr, bindings);
+            visit_exprs(args, bindings);
+        }
+        Expr::Lambda(params, body) => visit_procedure(params, body, bindings),
+        Expr::Block(statements) => bindings.with_scope(|bindings| {
+            for statement in statements {
+                visit_statement(statement, bindings);
+            }
+        }),
+    }
+}
+
+fn visit_exprs(exprs: &[Expr], bindings: &mut Bindings) {
+    for expr in exprs {
+        visit_expr(expr, bindings);
+    }
+}
+
+/// Returns whether every path through the body of a procedure, `statement`, gives the procedure a
+/// value. A path gives a value if it ends in a `return`, or if its last statement is an expression
+/// statement, whose value is the value of the block it ends.
+pub fn always_gives_value(statement: &Statement) -> bool {
+    match statement {
+        Statement::Expr(..) => true,
+        Statement::Block(statements) => {
+            statements.iter().any(always_returns)
+                || statements.last().is_some_and(always_gives_value)
+        }
+        Statement::If(_, then_statement, Some(else_statement), _) => {
+            always_gives_value(then_statement) && always_gives_value(else_statement)
+        }
+        statement => always_returns(statement),
+    }
+}
+
+/// Returns whether every path through `statement` ends in a `return`, so that it can never finish
+/// normally. A loop whose condition is `true` never finishes unless it has a `break`.
+fn always_returns(statement: &Statement) -> bool {
+    match statement {
+        Statement::Return(..) => true,
+        // the statements after one that always returns are never reached
+        Statement::Block(statements) => statements.iter().any(always_returns),
+        Statement::If(_, then_statement, Some(else_statement), _) => {
+            always_returns(then_statement) && always_returns(else_statement)
+        }
+        Statement::While(_, body, _, _, _) if breaks_loop(body) => false,
+        Statement::While(Expr::Bool(true), ..) => true,
+        // without a break, the else block is always run when the loop finishes
+        Statement::While(_, _, _, Some(else_statement), _) => always_returns(else_statement),
+        Statement::If(_, _, None, _)
+        | Statement::While(..)
+        | Statement::Expr(..)
+        | Statement::Let(..)
+        | Statement::Const(..)
+        | Statement::Assign(..)
+        | Statement::IndexAssign(..)
+        | Statement::Break
+        | Statement::Continue => false,
+    }
+}
+
+/// Returns whether `statement` contains a `break` out of the loop that it is the body of. A break
+/// in a nested loop only leaves that loop, except for a break in its else block, which is run
+/// after the nested loop has finished.
+fn breaks_loop(statement: &Statement) -> bool {
+    match statement {
+        Statement::Break => true,
+        Statement::Block(statements) => statements.iter().any(breaks_loop),
+        Statement::If(_, then_statement, else_statement_option, _) => {
+            breaks_loop(then_statement) || else_statement_option.as_deref().is_some_and(breaks_loop)
+        }
+        Statement::While(_, _, _, else_statement_option, _) => {
+            else_statement_option.as_deref().is_some_and(breaks_loop)
+        }
+        Statement::Expr(..)
+        | Statement::Let(..)
+        | Statement::Const(..)
+        | Statement::Assign(..)
+        | Statement::IndexAssign(..)
+        | Statement::Return(..)
+        | Statement::Continue => false,
+    }
+}
+
+/// Adds the name of each variable or procedure in the procedure with the given params and body
+/// whose value may be used to `used_names`. A procedure which is only called as a statement of
+/// its own has its result discarded, so its name is not added.
+pub fn find_used_results(params: &[Param], body: &Statement, used_names: &mut HashSet<String>) {
+    for param in params {
+        if let Some(default_expr) = &param.default {
+            find_used_results_in_expr(default_expr, used_names);
+        }
+    }
+    find_used_results_in_statement(body, used_names);
+}
+
+fn find_used_results_in_statement(statement: &Statement, used_names: &mut HashSet<String>) {
+    match statement {
+        Statement::Expr(Expr::Call(proc_expr, args), _) if matches!(**proc_expr, Expr::Var(_)) => {
+            find_used_results_in_exprs(args, used_names)
+        }
+        Statement::Expr(expr, _)
+        | Statement::Let(_, expr, _)
+        | Statement::Const(_, expr, _)
+        | Statement::Assign(_, expr, _)
+        | Statement::Return(Some(expr), _) => find_used_results_in_expr(expr, used_names),
+        Statement::IndexAssign(_, index_exprs, expr, _) => {
+            find_used_results_in_exprs(index_exprs, used_names);
+            find_used_results_in_expr(expr, used_names);
+        }
+        Statement::If(cond_expr, then_statement, else_statement_option, _) => {
+            find_used_results_in_expr(cond_expr, used_names);
+            find_used_results_in_statement(then_statement, used_names);
+            if let Some(else_statement) = else_statement_option {
+                find_used_results_in_statement(else_statement, used_names);
+            }
+        }
+        Statement::While(cond_expr, body, update_statement_option, else_statement_option, _) => {
+            find_used_results_in_expr(cond_expr, used_names);
+            find_used_results_in_statement(body, used_names);
+            for statement in [update_statement_option, else_statement_option]
+                .into_iter()
+                .flatten()
+            {
+                find_used_results_in_statement(statement, used_names);
+            }
+        }
+        Statement::Block(statements) => {
+            for statement in statements {
+                find_used_results_in_statement(statement, used_names);
+            }
+        }
+        Statement::Return(None, _) | Statement::Break | Statement::Continue => {}
+    }
+}
+
+fn find_used_results_in_expr(expr: &Expr, used_names: &mut HashSet<String>) {
+    match expr {
+        Expr::Nil | Expr::Num(_) | Expr::Float(_) | Expr::Bool(_) | Expr::Str(_) => {}
+        Expr::Var(name) => {
+            used_names.insert(name.to_string());
+        }
+        Expr::Binary(_, left, right) | Expr::Index(left, right) => {
+            find_used_results_in_expr(left, used_names);
+            find_used_results_in_expr(right, used_names);
+        }
+        Expr::Unary(_, operand) | Expr::Spread(operand) => {
+            find_used_results_in_expr(operand, used_names)
+        }
+        Expr::Comparison(first, comparisons) => {
+            find_used_results_in_expr(first, used_names);
+            for (_, expr) in comparisons {
+                find_used_results_in_expr(expr, used_names);
+            }
+        }
+        Expr::PrimitiveCall(_, args) | Expr::Sequence(args) => {
+            find_used_results_in_exprs(args, used_names)
+        }
+        Expr::Call(proc_expr, args) => {
+            find_used_results_in_expr(proc_expr, used_names);
+            find_used_results_in_exprs(args, used_names);
+        }
+        Expr::Lambda(params, body) => find_used_results(params, body, used_names),
+        Expr::Block(statements) => {
+            for statement in statements {
+                find_used_results_in_statement(statement, used_names);
+            }
+        }
+    }
+}
+
+fn find_used_results_in_exprs(exprs: &[Expr], used_names: &mut HashSet<String>) {
+    for expr in exprs {
+        find_used_results_in_expr(expr, used_names);
+    }
+}
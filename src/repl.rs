@@ -0,0 +1,97 @@
+use std::io::{BufRead, Write};
+
+use crate::{
+    desugar::Statement,
+    environment::Environment,
+    error::{LingerError, RuntimeError},
+    interpreter::{interp_statement, on_interpreter_thread, ControlFlow, Value},
+    parser::{parse_repl_input, ReplInput},
+    tokenizer::tokenize,
+    Writer,
+};
+
+/// An interactive session which evaluates one line of Linger at a time. Variables and top-level
+/// procedures defined on one line remain available on the following lines.
+pub struct Repl {
+    env: Environment,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self {
+            env: Environment::new(vec![]),
+        }
+    }
+
+    /// Evaluates a single line of input. On success, this function returns the value of the line
+    /// if it was a statement, or `None` if the line was blank or defined a top-level procedure.
+    pub fn eval_line(
+        &mut self,
+        line: &str,
+        writer: &mut Writer,
+    ) -> Result<Option<Value>, LingerError> {
+        let tokens = tokenize(line)?;
+        if tokens.is_empty() {
+            return Ok(None);
+        }
+
+        match parse_repl_input(tokens.as_slice())? {
+            ReplInput::Procedure(procedure) => {
+                self.env.insert_top_level_procedure(procedure);
+                Ok(None)
+            }
+            ReplInput::Statements(statements) => {
+                let value =
+                    on_interpreter_thread(|| interp_statements(&mut self.env, statements, writer))?;
+                Ok(Some(value))
+            }
+        }
+    }
+}
+
+/// Interprets the statements of a line in `env`, returning the value of the last statement.
+fn interp_statements(
+    env: &mut Environment,
+    statements: Vec<Statement>,
+    writer: &mut Writer,
+) -> Result<Value, RuntimeError> {
+    let mut value = Value::Nil;
+    for statement in statements {
+        value = match interp_statement(env, &statement, false, writer)? {
+            (_, ControlFlow::Break) => return Err(RuntimeError::BreakNotInLoop),
+            (_, ControlFlow::Continue) => return Err(RuntimeError::ContinueNotInLoop),
+            (value, _) => value,
+        };
+    }
+    Ok(value)
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs a REPL session, reading lines from `input` until it is exhausted. The value of each line
+/// is written to `writer` unless it is nil, and errors are written to stderr without ending the
+/// session.
+pub fn run_repl(input: impl BufRead, writer: &mut Writer) -> std::io::Result<()> {
+    let mut repl = Repl::new();
+
+    write!(writer.w, "> ")?;
+    writer.w.flush()?;
+    for line in input.lines() {
+        match repl.eval_line(line?.as_str(), writer) {
+            // calling `exit` ends the session
+            Err(LingerError::Runtime(RuntimeError::Exit(_))) => break,
+            Ok(Some(Value::Nil)) | Ok(None) => (),
+            Ok(Some(value)) => writeln!(writer.w, "{value}")?,
+            Err(e) => eprintln!("{e}"),
+        }
+        write!(writer.w, "> ")?;
+        writer.w.flush()?;
+    }
+    writeln!(writer.w)?;
+
+    Ok(())
+}
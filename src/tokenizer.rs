@@ -1,6 +1,7 @@
-use std::fmt;
+use std::{fmt, str::CharIndices};
 
 use regex::{Match, Regex};
+use serde::Serialize;
 
 use crate::error::TokenizerError::{self, *};
 
@@ -8,6 +9,9 @@ use crate::error::TokenizerError::{self, *};
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
 pub struct Token(pub TokenValue, pub usize, pub usize);
 
+/// A (line, column) position in a Linger program.
+pub type Position = (usize, usize);
+
 /// A Linger token value. This is an enum which represents the type of the
 /// token along with any associated data with that type.
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
@@ -15,7 +19,8 @@ pub struct Token(pub TokenValue, pub usize, pub usize);
 pub enum TokenValue {
     ID(String),
     STR(String),
-    NUM(f64),
+    NUM(i64),
+    FLOAT(f64),
     ASSIGN,
     OP(Operator),
     KW(Keyword),
@@ -27,19 +32,36 @@ pub enum TokenValue {
     L_SQUARE_BRACKET,
     R_SQUARE_BRACKET,
     SEMICOLON,
+    COLON,
     QUOTE,
+    /// The opening `r"` of a raw string literal, in which escape sequences are not processed.
+    RAW_QUOTE,
+    /// The opening backtick of a template literal.
+    BACKTICK,
+    /// A template literal, such as `` `hello ${name}` ``, split into its literal text and the
+    /// tokens of its embedded expressions.
+    TEMPLATE(Vec<TemplatePart>),
     COMMA,
     THIN_ARROW,
     DOUBLE_SLASH,
     DOUBLE_PLUS,
     DOUBLE_MINUS,
     DOT,
+    ELLIPSIS,
+}
+
+/// A piece of a template literal, which is either literal text or the tokens of an expression
+/// embedded with `${...}`.
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub enum TemplatePart {
+    Str(String),
+    Expr(Vec<Token>),
 }
 
 /// An operator. This enum represents all of the valid operators in the Linger
 /// programming language. The variants of this enum are the associated data for
 /// the [OP TokenValue](TokenValue::OP) variant.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize)]
 pub enum Operator {
     Plus,
     Minus,
@@ -52,6 +74,14 @@ pub enum Operator {
     GTE,
     Mod,
     Div,
+    /// Exponentiation. An integer raised to a negative integer power is promoted to a float.
+    Pow,
+    BitAnd,
+    BitOr,
+    /// Bitwise xor of two integers, or logical xor of two booleans.
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
     LogicOr,
     LogicAnd,
     LogicNot,
@@ -61,10 +91,20 @@ pub enum Operator {
     PostDecrement,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+/// An operator assignment, such as `+=`. The statement `x op= e` is desugared to `x = x op e`, so
+/// the logical operator assignments `&&=` and `||=` short-circuit and only evaluate `e` when the
+/// value of `x` does not already decide the result. Like `&&` and `||`, they always result in a
+/// bool, so `x &&= e` makes `x` a bool even if neither `x` nor `e` is one.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize)]
 pub enum AssignOp {
     Plus,
     Minus,
+    Times,
+    Div,
+    Mod,
+    Pow,
+    LogicAnd,
+    LogicOr,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -82,6 +122,9 @@ pub enum Keyword {
     Continue,
     For,
     Nil,
+    Switch,
+    Case,
+    Default,
 }
 
 const WHITESPACE_REGEX: &str = r"[[:space:]]+";
@@ -94,12 +137,16 @@ const GT_REGEX: &str = r">";
 const LTE_REGEX: &str = r"<=";
 const GTE_REGEX: &str = r">=";
 const ID_REGEX: &str = r"([a-zA-Z][a-zA-Z0-9_]*)\b";
-const NUM_REGEX: &str = r"\d*\.?\d+";
+const NUM_REGEX: &str = r"\d+";
+const HEX_NUM_REGEX: &str = r"0x[0-9a-zA-Z_]*";
+const BINARY_NUM_REGEX: &str = r"0b[0-9a-zA-Z_]*";
+const FLOAT_REGEX: &str = r"\d*\.\d+";
 const PLUS_REGEX: &str = r"\+";
 const MINUS_REGEX: &str = r"\-";
 const STAR_REGEX: &str = r"\*";
 const SLASH_REGEX: &str = r"/";
 const DOUBLE_SLASH_REGEX: &str = r"//";
+const DOUBLE_STAR_REGEX: &str = r"\*\*";
 const DOUBLE_PLUS_REGEX: &str = r"\+\+";
 const DOUBLE_MINUS_REGEX: &str = r"\-\-";
 const MOD_REGEX: &str = "%";
@@ -110,17 +157,33 @@ const R_CURLY_BRACKET_REGEX: &str = r"\}";
 const L_SQUARE_BRACKET_REGEX: &str = r"\[";
 const R_SQUARE_BRACKET_REGEX: &str = r"\]";
 const SEMICOLON_REGEX: &str = ";";
+const COLON_REGEX: &str = ":";
 const COMMA_REGEX: &str = ",";
 const QUOTE_REGEX: &str = "\"";
+const RAW_QUOTE_REGEX: &str = "r\"";
+const BACKTICK_REGEX: &str = "`";
 const LOGIC_OR_REGEX: &str = r"\|\|";
 const LOGIC_AND_REGEX: &str = "&&";
 const LOGIC_NOT_REGEX: &str = "!";
+const BIT_AND_REGEX: &str = "&";
+const BIT_OR_REGEX: &str = r"\|";
+const BIT_XOR_REGEX: &str = r"\^";
+const SHIFT_LEFT_REGEX: &str = "<<";
+const SHIFT_RIGHT_REGEX: &str = ">>";
 const ASSIGNMENT_PLUS_REGEX: &str = r"\+=";
 const ASSIGNMENT_MINUS_REGEX: &str = r"\-=";
+const ASSIGNMENT_TIMES_REGEX: &str = r"\*=";
+const ASSIGNMENT_DIV_REGEX: &str = r"/=";
+const ASSIGNMENT_MOD_REGEX: &str = r"%=";
+const ASSIGNMENT_POW_REGEX: &str = r"\*\*=";
+const ASSIGNMENT_LOGIC_AND_REGEX: &str = r"&&=";
+const ASSIGNMENT_LOGIC_OR_REGEX: &str = r"\|\|=";
 const DOT_REGEX: &str = r"\.";
+const ELLIPSIS_REGEX: &str = r"\.\.\.";
 
 /// Returns the [Tokens](Token) which make up the program `s`.
 pub fn tokenize(s: &str) -> Result<Vec<Token>, TokenizerError> {
+    let s = blank_block_comments(s)?;
     let enumerated_lines = s.split("\n").enumerate();
     let mut tokens: Vec<Token> = vec![];
     for (line_num, line) in enumerated_lines {
@@ -130,11 +193,88 @@ pub fn tokenize(s: &str) -> Result<Vec<Token>, TokenizerError> {
     Ok(tokens)
 }
 
+/// Returns a copy of the program `s` where every block comment (`/* ... */`) is replaced with
+/// whitespace. Newlines inside of block comments are kept, and every other character is replaced
+/// with as many spaces as it has bytes, so that the lines and columns of the remaining tokens are
+/// unchanged. Comment delimiters inside of string literals and line comments are ignored.
+fn blank_block_comments(s: &str) -> Result<String, TokenizerError> {
+    let mut blanked = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    let mut in_string = false;
+    let mut in_raw_string = false;
+    let mut in_template = false;
+    let mut in_line_comment = false;
+    let mut in_block_comment = false;
+    while let Some(char) = chars.next() {
+        if in_block_comment {
+            if char == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                blanked.push_str("  ");
+                in_block_comment = false;
+            } else if char == '\n' {
+                blanked.push('\n');
+            } else {
+                blanked.push_str(&" ".repeat(char.len_utf8()));
+            }
+            continue;
+        }
+
+        match char {
+            '\n' => {
+                in_string = false;
+                in_template = false;
+                in_line_comment = false;
+            }
+            '\\' if (in_string && !in_raw_string) || in_template => {
+                blanked.push(char);
+                if let Some(escaped_char) = chars.next() {
+                    blanked.push(escaped_char);
+                }
+                continue;
+            }
+            '"' if !in_line_comment && !in_template => {
+                if !in_string {
+                    in_raw_string = is_raw_string_prefix(&blanked);
+                }
+                in_string = !in_string;
+            }
+            '`' if !in_line_comment && !in_string => in_template = !in_template,
+            '/' if !in_string && !in_template && !in_line_comment => match chars.peek() {
+                Some('/') => in_line_comment = true,
+                Some('*') => {
+                    chars.next();
+                    blanked.push_str("  ");
+                    in_block_comment = true;
+                    continue;
+                }
+                _ => (),
+            },
+            _ => (),
+        }
+        blanked.push(char);
+    }
+
+    if in_block_comment {
+        return Err(UnterminatedComment);
+    }
+    Ok(blanked)
+}
+
+/// Returns whether a quote following `preceding` opens a raw string literal, which is the case
+/// when `preceding` ends with an `r` that is not part of a longer identifier.
+fn is_raw_string_prefix(preceding: &str) -> bool {
+    let mut preceding_chars = preceding.chars().rev();
+    preceding_chars.next() == Some('r')
+        && !preceding_chars
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
 /// Returns the [Tokens](Token) which make up the program `s`. This is a helper function which is
 /// wrapped by [tokenize]. This function also takes a line and column number which are passed to
 /// created token structures.
 fn tokenize_helper(s: &str, line_num: usize, col_num: usize) -> Result<Vec<Token>, TokenizerError> {
-    if s.len() == 0 {
+    if s.is_empty() {
         return Ok(vec![]);
     }
 
@@ -148,7 +288,7 @@ fn tokenize_helper(s: &str, line_num: usize, col_num: usize) -> Result<Vec<Token
         TokenValue::QUOTE => {
             let s = &s[token_length..];
             let mut string_token_content = String::new();
-            let mut enumerated_character_iter = s.chars().enumerate();
+            let mut enumerated_character_iter = s.char_indices();
             while let Some((index, char)) = enumerated_character_iter.next() {
                 match char {
                     '"' => {
@@ -160,39 +300,181 @@ fn tokenize_helper(s: &str, line_num: usize, col_num: usize) -> Result<Vec<Token
                         let mut rest_tokens = tokenize_helper(
                             &s[index + 1..],
                             line_num,
-                            // the "plus 2" is to account for the opening and closing quotes for the string literal
-                            col_num + string_token_content.len() + 2,
+                            // the "plus 1" is to account for the closing quote of the string literal
+                            col_num + token_length + index + 1,
                         )?;
                         tokens.append(&mut rest_tokens);
                         return Ok(tokens);
                     }
-                    '\\' => match enumerated_character_iter.nth(0) {
-                        Some((_, escaped_char)) => match escaped_char {
-                            'n' => string_token_content.push('\n'),
-                            'r' => string_token_content.push('\r'),
-                            't' => string_token_content.push('\t'),
-                            '\\' => string_token_content.push('\\'),
-                            '0' => string_token_content.push('\0'),
-                            '"' => string_token_content.push('"'),
-                            '\'' => string_token_content.push('\''),
-                            c => return Err(InvalidEscapeSequence(c)),
-                        },
+                    '\\' => match enumerated_character_iter.next() {
+                        Some((_, escaped_char)) => push_escaped_char(
+                            escaped_char,
+                            &mut enumerated_character_iter,
+                            &mut string_token_content,
+                            '"',
+                        )?,
                         None => return Err(UnterminatedStringLiteral),
                     },
                     _ => string_token_content.push(char),
                 }
             }
-            return Err(UnterminatedStringLiteral);
+            Err(UnterminatedStringLiteral)
+        }
+        TokenValue::RAW_QUOTE => {
+            let s = &s[token_length..];
+            // escape sequences are not processed, so a raw string ends at the first quote
+            let index = match s.find('"') {
+                Some(index) => index,
+                None => return Err(UnterminatedStringLiteral),
+            };
+            let mut tokens = vec![Token(
+                TokenValue::STR(s[..index].to_string()),
+                line_num,
+                col_num,
+            )];
+            let mut rest_tokens = tokenize_helper(
+                &s[index + 1..],
+                line_num,
+                col_num + token_length + index + 1,
+            )?;
+            tokens.append(&mut rest_tokens);
+            Ok(tokens)
+        }
+        TokenValue::BACKTICK => {
+            let s = &s[token_length..];
+            let mut parts = vec![];
+            let mut text = String::new();
+            let mut enumerated_character_iter = s.char_indices();
+            while let Some((index, char)) = enumerated_character_iter.next() {
+                match char {
+                    '`' => {
+                        if !text.is_empty() {
+                            parts.push(TemplatePart::Str(text));
+                        }
+                        let mut tokens =
+                            vec![Token(TokenValue::TEMPLATE(parts), line_num, col_num)];
+                        let mut rest_tokens = tokenize_helper(
+                            &s[index + 1..],
+                            line_num,
+                            col_num + token_length + index + 1,
+                        )?;
+                        tokens.append(&mut rest_tokens);
+                        return Ok(tokens);
+                    }
+                    '\\' => match enumerated_character_iter.next() {
+                        Some((_, escaped_char)) => push_escaped_char(
+                            escaped_char,
+                            &mut enumerated_character_iter,
+                            &mut text,
+                            '`',
+                        )?,
+                        None => return Err(UnterminatedTemplateLiteral),
+                    },
+                    '$' if s[index + 1..].starts_with('{') => {
+                        let expr_start = index + 2;
+                        let expr_end = expr_start + find_template_expr_end(&s[expr_start..])?;
+                        if !text.is_empty() {
+                            parts.push(TemplatePart::Str(std::mem::take(&mut text)));
+                        }
+                        parts.push(TemplatePart::Expr(tokenize_helper(
+                            &s[expr_start..expr_end],
+                            line_num,
+                            col_num + token_length + expr_start,
+                        )?));
+                        // the embedded expression and its closing bracket have been tokenized
+                        enumerated_character_iter.find(|(index, _)| *index == expr_end);
+                    }
+                    _ => text.push(char),
+                }
+            }
+            Err(UnterminatedTemplateLiteral)
         }
-        TokenValue::DOUBLE_SLASH => return Ok(vec![]),
+        TokenValue::DOUBLE_SLASH => Ok(vec![]),
         token_value => {
             let mut tokens = vec![Token(token_value, line_num, col_num)];
             let mut rest_tokens =
                 tokenize_helper(&s[token_length..], line_num, col_num + token_length)?;
             tokens.append(&mut rest_tokens);
-            return Ok(tokens);
+            Ok(tokens)
+        }
+    }
+}
+
+/// Pushes the character represented by the escape sequence `\escaped_char` onto `content`, reading
+/// the rest of a unicode escape from `chars`. The literal that the escape sequence is in ends with
+/// `quote`, and only template literals can escape their backticks and the `$` of `${`.
+fn push_escaped_char(
+    escaped_char: char,
+    chars: &mut CharIndices,
+    content: &mut String,
+    quote: char,
+) -> Result<(), TokenizerError> {
+    match escaped_char {
+        'n' => content.push('\n'),
+        'r' => content.push('\r'),
+        't' => content.push('\t'),
+        '\\' => content.push('\\'),
+        '0' => content.push('\0'),
+        '"' => content.push('"'),
+        '\'' => content.push('\''),
+        '`' | '$' if quote == '`' => content.push(escaped_char),
+        'u' => {
+            let mut code_point_digits = String::new();
+            if !matches!(chars.next(), Some((_, '{'))) {
+                return Err(InvalidUnicodeEscape(code_point_digits));
+            }
+            loop {
+                match chars.next() {
+                    Some((_, '}')) => break,
+                    Some((_, c)) if c == quote => {
+                        return Err(InvalidUnicodeEscape(code_point_digits))
+                    }
+                    None => return Err(InvalidUnicodeEscape(code_point_digits)),
+                    Some((_, digit)) => code_point_digits.push(digit),
+                }
+            }
+            // from_str_radix also accepts a sign, so the digits are checked first. No codepoint
+            // has more than six hex digits.
+            let is_valid_hex = (1..=6).contains(&code_point_digits.len())
+                && code_point_digits.chars().all(|c| c.is_ascii_hexdigit());
+            if !is_valid_hex {
+                return Err(InvalidUnicodeEscape(code_point_digits));
+            }
+            match u32::from_str_radix(&code_point_digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+            {
+                Some(c) => content.push(c),
+                None => return Err(InvalidUnicodeEscape(code_point_digits)),
+            }
         }
+        c => return Err(InvalidEscapeSequence(c)),
     }
+    Ok(())
+}
+
+/// Returns the index of the `}` which closes an expression embedded in a template literal, where
+/// `s` begins just after the `${`. Brackets inside of string and template literals in the
+/// expression are ignored.
+fn find_template_expr_end(s: &str) -> Result<usize, TokenizerError> {
+    let mut depth = 0;
+    let mut quote_option = None;
+    let mut enumerated_character_iter = s.char_indices();
+    while let Some((index, char)) = enumerated_character_iter.next() {
+        match (quote_option, char) {
+            (Some(_), '\\') => {
+                enumerated_character_iter.next();
+            }
+            (Some(quote), _) if char == quote => quote_option = None,
+            (Some(_), _) => (),
+            (None, '"' | '`') => quote_option = Some(char),
+            (None, '{') => depth += 1,
+            (None, '}') if depth == 0 => return Ok(index),
+            (None, '}') => depth -= 1,
+            (None, _) => (),
+        }
+    }
+    Err(UnterminatedTemplateExpression)
 }
 
 /// Tries to get a token beginning at the start of `s`. On success, this function returns an option
@@ -204,48 +486,70 @@ fn get_token_value(s: &str) -> Result<(Option<TokenValue>, usize), TokenizerErro
         Ok((None, mat.end()))
 
     // KEYWORDS
-    } else if let Some(mat) = find("if", s) {
+    } else if let Some(mat) = find(r"if\b", s) {
         Ok((Some(TokenValue::KW(Keyword::If)), mat.end()))
-    } else if let Some(mat) = find("else", s) {
+    } else if let Some(mat) = find(r"else\b", s) {
         Ok((Some(TokenValue::KW(Keyword::Else)), mat.end()))
-    } else if let Some(mat) = find("proc", s) {
+    } else if let Some(mat) = find(r"proc\b", s) {
         Ok((Some(TokenValue::KW(Keyword::Proc)), mat.end()))
-    } else if let Some(mat) = find("let", s) {
+    } else if let Some(mat) = find(r"let\b", s) {
         Ok((Some(TokenValue::KW(Keyword::Let)), mat.end()))
-    } else if let Some(mat) = find("true", s) {
+    } else if let Some(mat) = find(r"true\b", s) {
         Ok((Some(TokenValue::KW(Keyword::True)), mat.end()))
-    } else if let Some(mat) = find("false", s) {
+    } else if let Some(mat) = find(r"false\b", s) {
         Ok((Some(TokenValue::KW(Keyword::False)), mat.end()))
-    } else if let Some(mat) = find("return", s) {
+    } else if let Some(mat) = find(r"return\b", s) {
         Ok((Some(TokenValue::KW(Keyword::Return)), mat.end()))
-    } else if let Some(mat) = find("while", s) {
+    } else if let Some(mat) = find(r"while\b", s) {
         Ok((Some(TokenValue::KW(Keyword::While)), mat.end()))
-    } else if let Some(mat) = find("break", s) {
+    } else if let Some(mat) = find(r"break\b", s) {
         Ok((Some(TokenValue::KW(Keyword::Break)), mat.end()))
-    } else if let Some(mat) = find("continue", s) {
+    } else if let Some(mat) = find(r"continue\b", s) {
         Ok((Some(TokenValue::KW(Keyword::Continue)), mat.end()))
-    } else if let Some(mat) = find("for", s) {
+    } else if let Some(mat) = find(r"for\b", s) {
         Ok((Some(TokenValue::KW(Keyword::For)), mat.end()))
-    } else if let Some(mat) = find("const", s) {
+    } else if let Some(mat) = find(r"const\b", s) {
         Ok((Some(TokenValue::KW(Keyword::Const)), mat.end()))
-    } else if let Some(mat) = find("nil", s) {
+    } else if let Some(mat) = find(r"nil\b", s) {
         Ok((Some(TokenValue::KW(Keyword::Nil)), mat.end()))
+    } else if let Some(mat) = find(r"switch\b", s) {
+        Ok((Some(TokenValue::KW(Keyword::Switch)), mat.end()))
+    } else if let Some(mat) = find(r"case\b", s) {
+        Ok((Some(TokenValue::KW(Keyword::Case)), mat.end()))
+    } else if let Some(mat) = find(r"default\b", s) {
+        Ok((Some(TokenValue::KW(Keyword::Default)), mat.end()))
+
+    // THREE-CHARACTER TOKENS
+    } else if let Some(mat) = find(ELLIPSIS_REGEX, s) {
+        Ok((Some(TokenValue::ELLIPSIS), mat.end()))
 
     // TWO-CHARACTER TOKENS
     } else if let Some(mat) = find(NE_REGEX, s) {
         Ok((Some(TokenValue::OP(Operator::Ne)), mat.end()))
     } else if let Some(mat) = find(EQ_REGEX, s) {
         Ok((Some(TokenValue::OP(Operator::Eq)), mat.end()))
+    } else if let Some(mat) = find(SHIFT_LEFT_REGEX, s) {
+        Ok((Some(TokenValue::OP(Operator::ShiftLeft)), mat.end()))
+    } else if let Some(mat) = find(SHIFT_RIGHT_REGEX, s) {
+        Ok((Some(TokenValue::OP(Operator::ShiftRight)), mat.end()))
     } else if let Some(mat) = find(LTE_REGEX, s) {
         Ok((Some(TokenValue::OP(Operator::LTE)), mat.end()))
     } else if let Some(mat) = find(GTE_REGEX, s) {
         Ok((Some(TokenValue::OP(Operator::GTE)), mat.end()))
+    } else if let Some(mat) = find(ASSIGNMENT_POW_REGEX, s) {
+        Ok((Some(TokenValue::ASSIGN_OP(AssignOp::Pow)), mat.end()))
+    } else if let Some(mat) = find(ASSIGNMENT_LOGIC_AND_REGEX, s) {
+        Ok((Some(TokenValue::ASSIGN_OP(AssignOp::LogicAnd)), mat.end()))
+    } else if let Some(mat) = find(ASSIGNMENT_LOGIC_OR_REGEX, s) {
+        Ok((Some(TokenValue::ASSIGN_OP(AssignOp::LogicOr)), mat.end()))
     } else if let Some(mat) = find(LOGIC_AND_REGEX, s) {
         Ok((Some(TokenValue::OP(Operator::LogicAnd)), mat.end()))
     } else if let Some(mat) = find(LOGIC_OR_REGEX, s) {
         Ok((Some(TokenValue::OP(Operator::LogicOr)), mat.end()))
     } else if let Some(mat) = find(DOUBLE_SLASH_REGEX, s) {
         Ok((Some(TokenValue::DOUBLE_SLASH), mat.end()))
+    } else if let Some(mat) = find(DOUBLE_STAR_REGEX, s) {
+        Ok((Some(TokenValue::OP(Operator::Pow)), mat.end()))
     } else if let Some(mat) = find(THIN_ARROW_REGEX, s) {
         Ok((Some(TokenValue::THIN_ARROW), mat.end()))
     } else if let Some(mat) = find(DOUBLE_PLUS_REGEX, s) {
@@ -256,6 +560,12 @@ fn get_token_value(s: &str) -> Result<(Option<TokenValue>, usize), TokenizerErro
         Ok((Some(TokenValue::ASSIGN_OP(AssignOp::Plus)), mat.end()))
     } else if let Some(mat) = find(ASSIGNMENT_MINUS_REGEX, s) {
         Ok((Some(TokenValue::ASSIGN_OP(AssignOp::Minus)), mat.end()))
+    } else if let Some(mat) = find(ASSIGNMENT_TIMES_REGEX, s) {
+        Ok((Some(TokenValue::ASSIGN_OP(AssignOp::Times)), mat.end()))
+    } else if let Some(mat) = find(ASSIGNMENT_DIV_REGEX, s) {
+        Ok((Some(TokenValue::ASSIGN_OP(AssignOp::Div)), mat.end()))
+    } else if let Some(mat) = find(ASSIGNMENT_MOD_REGEX, s) {
+        Ok((Some(TokenValue::ASSIGN_OP(AssignOp::Mod)), mat.end()))
 
     // ONE-CHARACTER TOKENS
     } else if let Some(mat) = find(ASSIGN_REGEX, s) {
@@ -288,19 +598,46 @@ fn get_token_value(s: &str) -> Result<(Option<TokenValue>, usize), TokenizerErro
         Ok((Some(TokenValue::R_SQUARE_BRACKET), mat.end()))
     } else if let Some(mat) = find(SEMICOLON_REGEX, s) {
         Ok((Some(TokenValue::SEMICOLON), mat.end()))
+    } else if let Some(mat) = find(COLON_REGEX, s) {
+        Ok((Some(TokenValue::COLON), mat.end()))
     } else if let Some(mat) = find(COMMA_REGEX, s) {
         Ok((Some(TokenValue::COMMA), mat.end()))
+    } else if let Some(mat) = find(RAW_QUOTE_REGEX, s) {
+        Ok((Some(TokenValue::RAW_QUOTE), mat.end()))
     } else if let Some(mat) = find(QUOTE_REGEX, s) {
         Ok((Some(TokenValue::QUOTE), mat.end()))
+    } else if let Some(mat) = find(BACKTICK_REGEX, s) {
+        Ok((Some(TokenValue::BACKTICK), mat.end()))
     } else if let Some(mat) = find(LOGIC_NOT_REGEX, s) {
         Ok((Some(TokenValue::OP(Operator::LogicNot)), mat.end()))
+    } else if let Some(mat) = find(BIT_AND_REGEX, s) {
+        Ok((Some(TokenValue::OP(Operator::BitAnd)), mat.end()))
+    } else if let Some(mat) = find(BIT_OR_REGEX, s) {
+        Ok((Some(TokenValue::OP(Operator::BitOr)), mat.end()))
+    } else if let Some(mat) = find(BIT_XOR_REGEX, s) {
+        Ok((Some(TokenValue::OP(Operator::BitXor)), mat.end()))
 
     // VARIABLE-LENGTH TOKENS
     } else if let Some(mat) = find(ID_REGEX, s) {
         Ok((Some(TokenValue::ID(mat.as_str().to_string())), mat.end()))
+    } else if let Some(mat) = find(HEX_NUM_REGEX, s) {
+        Ok((
+            Some(TokenValue::NUM(parse_integer_literal(mat.as_str(), 2, 16)?)),
+            mat.end(),
+        ))
+    } else if let Some(mat) = find(BINARY_NUM_REGEX, s) {
+        Ok((
+            Some(TokenValue::NUM(parse_integer_literal(mat.as_str(), 2, 2)?)),
+            mat.end(),
+        ))
+    } else if let Some(mat) = find(FLOAT_REGEX, s) {
+        Ok((
+            Some(TokenValue::FLOAT(mat.as_str().parse::<f64>().expect("a match with the FLOAT_REGEX should imply that the string slice can be parsed into an f64"))),
+            mat.end(),
+        ))
     } else if let Some(mat) = find(NUM_REGEX, s) {
         Ok((
-            Some(TokenValue::NUM(mat.as_str().parse::<f64>().expect("a match with the NUM_REGEX should imply that the string slice can be parsed into am i64"))),
+            Some(TokenValue::NUM(parse_integer_literal(mat.as_str(), 0, 10)?)),
             mat.end(),
         ))
     } else if let Some(mat) = find(DOT_REGEX, s) {
@@ -311,21 +648,36 @@ fn get_token_value(s: &str) -> Result<(Option<TokenValue>, usize), TokenizerErro
         Err(UnknownToken({
             let mut split =
                 s.split(|c: char| str_to_regex(WHITESPACE_REGEX).is_match(c.to_string().as_str()));
-            let unknown_token = split.nth(0).expect("some non-whitespace text since whitespace would have been matched on the first branch of the if statement");
-            format!("{}", unknown_token).to_string()
+            let unknown_token = split.next().expect("some non-whitespace text since whitespace would have been matched on the first branch of the if statement");
+            unknown_token.to_string()
         }))
     }
 }
 
+/// Parses the integer literal `literal` in the given radix, ignoring the first `prefix_length`
+/// characters of the literal (such as the `0x` of a hexadecimal literal). This function returns a
+/// [TokenizerError] if the literal has no digits, has a digit that is invalid in the radix, or
+/// does not fit in an i64.
+fn parse_integer_literal(
+    literal: &str,
+    prefix_length: usize,
+    radix: u32,
+) -> Result<i64, TokenizerError> {
+    let digits = &literal[prefix_length..];
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return Err(InvalidNumberLiteral(literal.to_string()));
+    }
+    i64::from_str_radix(digits, radix).map_err(|_| IntegerLiteralOverflow(literal.to_string()))
+}
+
 /// Takes a string and returns the corresponding [Regex].
 fn str_to_regex(s: &str) -> Regex {
-    return Regex::new(format!("^({s})").as_str())
-        .expect("strings to be valid regular expressions");
+    Regex::new(format!("^({s})").as_str()).expect("strings to be valid regular expressions")
 }
 
 /// Checks if `s` starts with the regular expression represented by `re`.
 fn find<'a>(re: &'a str, s: &'a str) -> Option<Match<'a>> {
-    return str_to_regex(re).find(s);
+    str_to_regex(re).find(s)
 }
 
 impl fmt::Display for AssignOp {
@@ -333,6 +685,12 @@ impl fmt::Display for AssignOp {
         match self {
             AssignOp::Plus => write!(f, "+="),
             AssignOp::Minus => write!(f, "-="),
+            AssignOp::Times => write!(f, "*="),
+            AssignOp::Div => write!(f, "/="),
+            AssignOp::Mod => write!(f, "%="),
+            AssignOp::Pow => write!(f, "**="),
+            AssignOp::LogicAnd => write!(f, "&&="),
+            AssignOp::LogicOr => write!(f, "||="),
         }
     }
 }
@@ -353,6 +711,12 @@ impl fmt::Display for Operator {
             Operator::LogicOr => write!(f, "||"),
             Operator::LogicAnd => write!(f, "&&"),
             Operator::Div => write!(f, "/"),
+            Operator::Pow => write!(f, "**"),
+            Operator::BitAnd => write!(f, "&"),
+            Operator::BitOr => write!(f, "|"),
+            Operator::BitXor => write!(f, "^"),
+            Operator::ShiftLeft => write!(f, "<<"),
+            Operator::ShiftRight => write!(f, ">>"),
             Operator::LogicNot => write!(f, "!"),
             Operator::PreIncrement => write!(f, "++"),
             Operator::PostIncrement => write!(f, "++"),
@@ -378,6 +742,9 @@ impl fmt::Display for Keyword {
             Keyword::For => write!(f, "for"),
             Keyword::Const => write!(f, "const"),
             Keyword::Nil => write!(f, "nil"),
+            Keyword::Switch => write!(f, "switch"),
+            Keyword::Case => write!(f, "case"),
+            Keyword::Default => write!(f, "default"),
         }
     }
 }
@@ -387,16 +754,36 @@ impl fmt::Display for TokenValue {
         match self {
             TokenValue::ID(id) => write!(f, "{id}"),
             TokenValue::NUM(n) => write!(f, "{n}"),
+            TokenValue::FLOAT(n) => write!(f, "{n}"),
             TokenValue::ASSIGN => write!(f, "="),
             TokenValue::LPAREN => write!(f, "("),
             TokenValue::RPAREN => write!(f, ")"),
             TokenValue::L_CURLY_BRACKET => write!(f, "{{"),
             TokenValue::R_CURLY_BRACKET => write!(f, "}}"),
             TokenValue::SEMICOLON => write!(f, ";"),
+            TokenValue::COLON => write!(f, ":"),
             TokenValue::COMMA => write!(f, ","),
             TokenValue::OP(op) => write!(f, "{op}"),
             TokenValue::QUOTE => write!(f, "\""),
+            TokenValue::RAW_QUOTE => write!(f, "r\""),
             TokenValue::STR(s) => write!(f, "\"{s}\""),
+            TokenValue::BACKTICK => write!(f, "`"),
+            TokenValue::TEMPLATE(parts) => {
+                write!(f, "`")?;
+                for part in parts {
+                    match part {
+                        TemplatePart::Str(s) => write!(f, "{s}")?,
+                        TemplatePart::Expr(tokens) => {
+                            let tokens: Vec<String> = tokens
+                                .iter()
+                                .map(|Token(value, ..)| value.to_string())
+                                .collect();
+                            write!(f, "${{{}}}", tokens.join(" "))?
+                        }
+                    }
+                }
+                write!(f, "`")
+            }
             TokenValue::THIN_ARROW => write!(f, "->"),
             TokenValue::DOUBLE_SLASH => write!(f, "//"),
             TokenValue::KW(kw) => write!(f, "{kw}"),
@@ -404,6 +791,7 @@ impl fmt::Display for TokenValue {
             TokenValue::DOUBLE_MINUS => write!(f, "--"),
             TokenValue::ASSIGN_OP(op) => write!(f, "{op}"),
             TokenValue::DOT => write!(f, "."),
+            TokenValue::ELLIPSIS => write!(f, "..."),
             TokenValue::L_SQUARE_BRACKET => write!(f, "["),
             TokenValue::R_SQUARE_BRACKET => write!(f, "]"),
         }
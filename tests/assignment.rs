@@ -5,7 +5,7 @@ use linger::error::{ParseError, RuntimeError};
 use predicates::prelude::predicate::str::{contains, starts_with};
 
 fn file_name_to_path(s: &str) -> String {
-    return format!("test_programs/assignment/{}.ling", s);
+    format!("test_programs/assignment/{}.ling", s)
 }
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
@@ -40,6 +40,16 @@ fn const_() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn keyword_prefixed_names() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("keyword_prefixed_names"));
+    cmd.assert().success().stdout("3 2 3 4 5 6 7");
+
+    Ok(())
+}
+
 #[test]
 fn err_keyword_as_var() -> TestResult {
     let mut cmd = Command::cargo_bin("linger-core")?;
@@ -87,3 +97,68 @@ fn err_reassign_top_level_proc() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn err_assign_undeclared_var() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-assign_undeclared_var"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            RuntimeError::UnknownVariable("x".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-operator_assign_undeclared_var"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            RuntimeError::UnknownVariable("x".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn destructuring() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("destructuring"));
+    cmd.assert().success().stdout("1 2 c 3 [2] c  10");
+
+    Ok(())
+}
+
+#[test]
+fn err_destructure_mismatch() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-destructure_mismatch"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            RuntimeError::DestructureMismatch(3, 2).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn err_destructure_keyword() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-destructure_keyword"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            ParseError::KeywordAsVar("while".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
@@ -1,10 +1,14 @@
 use std::process::Command;
 
 use assert_cmd::prelude::*;
-use predicates::prelude::predicate::str::contains;
+use linger::{
+    error::{ParseError, RuntimeError},
+    tokenizer::{Token, TokenValue},
+};
+use predicates::prelude::predicate::str::{contains, starts_with};
 
 fn file_name_to_path(s: &str) -> String {
-    return format!("test_programs/control_flow/{}.ling", s);
+    format!("test_programs/control_flow/{}.ling", s)
 }
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
@@ -68,3 +72,55 @@ fn nested_return() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn if_truthiness() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("if_truthiness"));
+    cmd.assert().success().stdout(
+        "[falsy, falsy, falsy, falsy, falsy, falsy, falsy, truthy, truthy, truthy, truthy, truthy, truthy]",
+    );
+
+    Ok(())
+}
+
+#[test]
+fn switch() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("switch"));
+    cmd.assert()
+        .success()
+        .stdout("evaluated first one two many");
+
+    Ok(())
+}
+
+#[test]
+fn err_switch_missing_colon() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-switch_missing_colon"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        ParseError::Expected(
+            TokenValue::COLON,
+            Token(TokenValue::ID("print".to_string()), 4, 7),
+        )
+        .to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_else_if_condition_position() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-else_if_condition_position"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::At(Box::new(RuntimeError::DivisionByZero), (7, 5)).to_string(),
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,278 @@
+use linger::{
+    ast_to_json,
+    environment::{Environment, DEFAULT_MAX_CALL_DEPTH},
+    error::{LingerError, ParseError, RuntimeError, TokenizerError},
+    fold::fold_program,
+    interp_to_buffer,
+    interpreter::{apply, interp_program, interp_program_with_options, InterpOptions, Value},
+    parse_str,
+    parser::parse_program,
+    run_str,
+    tokenizer::{tokenize, Token, TokenValue},
+    Writer,
+};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+#[test]
+fn run_str_returns_main_value() -> TestResult {
+    let value = run_str("proc main() { return 1 + 2; }")?;
+    assert!(matches!(value, Value::Num(3)));
+
+    Ok(())
+}
+
+#[test]
+fn parse_str_does_not_interpret() -> TestResult {
+    let program = parse_str("proc main() { return a; }")?;
+    assert!(program.procedures.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn interp_to_buffer_captures_output() -> TestResult {
+    let mut buf: Vec<u8> = vec![];
+    interp_to_buffer(
+        "proc main() { print(\"a\"); print(\"b\"); }".to_string(),
+        &mut buf,
+    )?;
+    assert_eq!(String::from_utf8(buf)?, "ab");
+
+    Ok(())
+}
+
+#[test]
+fn err_interp_to_buffer_undefined_procedure() {
+    let mut buf: Vec<u8> = vec![];
+    let result = interp_to_buffer(
+        "proc main() { print(\"a\"); missing(); }".to_string(),
+        &mut buf,
+    );
+    assert_eq!(
+        result,
+        Err(ParseError::UndefinedProcedure("missing".to_string()).to_string())
+    );
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn err_run_str_runtime_error() {
+    match run_str("proc main() { return a; }") {
+        Err(LingerError::Runtime(RuntimeError::At(error, _))) => {
+            assert!(matches!(*error, RuntimeError::UnknownVariable(_)))
+        }
+        result => panic!(
+            "expected an unknown variable error, instead got {:?}",
+            result
+        ),
+    }
+}
+
+#[test]
+fn err_max_call_depth_exceeded() -> TestResult {
+    let program = parse_str("proc f() { return f(); } proc main() { f(); }")?;
+    let writer = &mut Writer::new(Box::new(std::io::sink()));
+    let options = InterpOptions {
+        max_call_depth: 3,
+        ..Default::default()
+    };
+    match interp_program_with_options(program, options, writer) {
+        Err(RuntimeError::At(error, _)) => {
+            assert!(matches!(*error, RuntimeError::StackOverflow(3)))
+        }
+        result => panic!("expected a stack overflow error, instead got {:?}", result),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn err_run_str_default_call_depth_exceeded() {
+    // the test runs on a thread with a small stack, so this only returns an error if the program
+    // is interpreted on a thread of its own
+    match run_str("proc f(n) { return f(n + 1); } proc main() { f(0); }") {
+        Err(LingerError::Runtime(RuntimeError::At(error, _))) => assert!(matches!(
+            *error,
+            RuntimeError::StackOverflow(DEFAULT_MAX_CALL_DEPTH)
+        )),
+        result => panic!("expected a stack overflow error, instead got {:?}", result),
+    }
+}
+
+#[test]
+fn err_parse_str_parse_error() {
+    assert!(matches!(
+        parse_str("proc main() { return 1 }"),
+        Err(LingerError::Parse(_))
+    ));
+}
+
+#[test]
+fn parse_str_desugars_switch_to_if_chain() -> TestResult {
+    let program = parse_str(
+        "proc main() { switch (2) { case 1: print(1); case 2: print(2); default: print(3); } }",
+    )?;
+    let desugared = program.to_string();
+    assert!(desugared.contains("const #switch = 2;"));
+    assert!(desugared.contains("if ((#switch == 1)) {"));
+    assert!(desugared.contains("} else if ((#switch == 2)) {"));
+    assert!(desugared.contains("} else {"));
+
+    Ok(())
+}
+
+#[test]
+fn tokenize_hex_and_binary_literals() -> TestResult {
+    let tokens = tokenize("0xFF 0b1010")?;
+    assert_eq!(
+        tokens,
+        vec![
+            Token(TokenValue::NUM(255), 1, 1),
+            Token(TokenValue::NUM(10), 1, 6)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn tokenize_raw_string() -> TestResult {
+    let tokens = tokenize(r#"r"C:\path\n" r"/* \ */""#)?;
+    assert_eq!(
+        tokens,
+        vec![
+            Token(TokenValue::STR(r"C:\path\n".to_string()), 1, 1),
+            Token(TokenValue::STR(r"/* \ */".to_string()), 1, 14)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn err_tokenize_unterminated_raw_string() {
+    assert!(matches!(
+        tokenize(r#"r"C:\path"#),
+        Err(TokenizerError::UnterminatedStringLiteral)
+    ));
+}
+
+#[test]
+fn ast_to_json_contains_program_structure() -> TestResult {
+    let json = ast_to_json("proc double(n) { return n * 2; } proc main() { print(double(2)); }")?;
+    let ast: serde_json::Value = serde_json::from_str(json.as_str())?;
+
+    assert_eq!(ast["procedures"][0]["name"], "double");
+    assert_eq!(ast["procedures"][0]["params"][0]["name"], "n");
+    assert_eq!(
+        ast["procedures"][0]["body"]["Block"][0]["Return"][0]["Binary"][0],
+        "Times"
+    );
+    assert_eq!(
+        ast["main"]["Block"][0]["Expr"][0]["PrimitiveCall"][0],
+        "Print"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_many_statements() -> TestResult {
+    // the tokens of a single statement are repeated, since only the parser is being tested
+    let statement_tokens = tokenize("x += 1;")?;
+    // the statements are parsed as the body of a procedure, and as the body of a switch case
+    for (prefix, suffix) in [("", ""), ("switch (0) { case 0:", "}")] {
+        let mut tokens = tokenize(&format!("proc main() {{ let x = 0; {prefix}"))?;
+        for _ in 0..10_000 {
+            tokens.extend(statement_tokens.iter().cloned());
+        }
+        tokens.extend(tokenize(&format!("{suffix} return x; }}"))?);
+
+        let program = parse_program(&tokens)?;
+        let value = interp_program(program, &mut Writer::new(Box::new(std::io::sink())))?;
+        assert!(matches!(value, Value::Num(10_000)));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parse_many_procedures() -> TestResult {
+    let proc_tokens = tokenize("proc f() { return 0; }")?;
+    let mut tokens = vec![];
+    for i in 0..10_000 {
+        tokens.extend(proc_tokens.iter().map(|token| match token {
+            Token(TokenValue::ID(_), line, col) => {
+                Token(TokenValue::ID(format!("f{i}")), *line, *col)
+            }
+            token => token.clone(),
+        }));
+    }
+    tokens.extend(tokenize("proc main() { return f9999(); }")?);
+
+    let program = parse_program(&tokens)?;
+    assert_eq!(program.procedures.len(), 10_000);
+
+    Ok(())
+}
+
+#[test]
+fn apply_lambda_with_closure() -> TestResult {
+    let callee = run_str("proc main() { let offset = 10; return (x, y) -> x * y + offset; }")?;
+    let writer = &mut Writer::new(Box::new(std::io::sink()));
+    let env = &mut Environment::new(vec![]);
+
+    let value = apply(env, callee, vec![Value::Num(3), Value::Num(4)], writer)?;
+    assert!(matches!(value, Value::Num(22)));
+
+    Ok(())
+}
+
+#[test]
+fn err_apply_arg_mismatch_and_non_procedure() -> TestResult {
+    let callee = run_str("proc main() { return (x) -> x; }")?;
+    let writer = &mut Writer::new(Box::new(std::io::sink()));
+    let env = &mut Environment::new(vec![]);
+
+    match apply(env, callee, vec![], writer) {
+        Err(RuntimeError::ArgMismatch(name, 1, 0)) => assert_eq!(name, "<lambda (x)>"),
+        result => panic!("expected an arg mismatch error, instead got {:?}", result),
+    }
+    match apply(env, Value::Num(1), vec![], writer) {
+        Err(RuntimeError::BadArg(Value::Num(1))) => {}
+        result => panic!("expected a bad arg error, instead got {:?}", result),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fold_program_folds_literal_expressions() -> TestResult {
+    let program =
+        parse_str("proc main() { print(\"a\" + \"b\" + \"c\", 2 * 3 + -1, 1.5 + 1, x + 1 + 2); }")?;
+    let folded = serde_json::to_value(fold_program(program))?;
+
+    let args = &folded["main"]["Block"][0]["Expr"][0]["PrimitiveCall"][1];
+    assert_eq!(args[0], serde_json::json!({ "Str": "abc" }));
+    assert_eq!(args[1], serde_json::json!({ "Num": 5 }));
+    assert_eq!(args[2], serde_json::json!({ "Float": 2.5 }));
+    // addition is left-associative, so `x + 1` is added to 2 rather than 1 + 2 being folded
+    assert_eq!(args[3]["Binary"][2], serde_json::json!({ "Num": 2 }));
+
+    Ok(())
+}
+
+#[test]
+fn fold_program_leaves_failing_expressions() -> TestResult {
+    let program = parse_str("proc main() { return 9223372036854775807 + 1; }")?;
+    let folded = serde_json::to_value(fold_program(program))?;
+
+    assert_eq!(folded["main"]["Block"][0]["Return"][0]["Binary"][0], "Plus");
+    assert!(matches!(
+        run_str("proc main() { return 9223372036854775807 + 1; }"),
+        Err(LingerError::Runtime(_))
+    ));
+
+    Ok(())
+}
@@ -5,7 +5,7 @@ use linger::{error::RuntimeError, interpreter::Value};
 use predicates::prelude::predicate::str::starts_with;
 
 fn file_name_to_path(s: &str) -> String {
-    return format!("test_programs/lists/{}.ling", s);
+    format!("test_programs/lists/{}.ling", s)
 }
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
@@ -68,7 +68,7 @@ fn err_head_non_list() -> TestResult {
 
     cmd.arg(file_name_to_path("err-head_non_list"));
     cmd.assert().failure().stdout("").stderr(starts_with(
-        RuntimeError::ExpectedList(Value::Num(4.0)).to_string(),
+        RuntimeError::ExpectedList(Value::Num(4)).to_string(),
     ));
 
     Ok(())
@@ -92,7 +92,7 @@ fn err_indexing_non_list() -> TestResult {
 
     cmd.arg(file_name_to_path("err-indexing_non_list"));
     cmd.assert().failure().stdout("").stderr(starts_with(
-        RuntimeError::NotIndexable(Value::Num(10.0)).to_string(),
+        RuntimeError::NotIndexable(Value::Num(10)).to_string(),
     ));
 
     Ok(())
@@ -134,8 +134,144 @@ fn err_index_not_an_integer() -> TestResult {
 
     cmd_float.arg(file_name_to_path("err-index_not_an_integer_float"));
     cmd_float.assert().failure().stdout("").stderr(starts_with(
-        RuntimeError::ExpectedInteger(Value::Num(3.14)).to_string(),
+        RuntimeError::ExpectedInteger(Value::Float(2.5)).to_string(),
     ));
 
     Ok(())
 }
+
+#[test]
+fn list_literals() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("list_literals"));
+    cmd.assert()
+        .success()
+        .stdout(starts_with("20 [] [1, [2, 3]] 2"));
+
+    Ok(())
+}
+
+#[test]
+fn err_list_literal_index_out_of_bounds() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-list_literal_index_out_of_bounds"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with(RuntimeError::IndexOutOfBounds(2).to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn err_unterminated_list_literal() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-unterminated_list_literal"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with("unexpected token \";\""));
+
+    Ok(())
+}
+
+#[test]
+fn list_element_assignment() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("list_element_assignment"));
+    cmd.assert()
+        .success()
+        .stdout(starts_with("[99, 2, three] [[1, 2], [100, 4]]"));
+
+    Ok(())
+}
+
+#[test]
+fn err_assign_index_out_of_bounds() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-assign_index_out_of_bounds"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with(RuntimeError::IndexOutOfBounds(3).to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn err_assign_index_const() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-assign_index_const"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::ReassignConstant("a".to_string()).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn push_pop_concat() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("push_pop_concat"));
+    cmd.assert()
+        .success()
+        .stdout(starts_with("[1, 2] [1, 2, 3] 3 [1, 2, 4, 5] []"));
+
+    Ok(())
+}
+
+#[test]
+fn err_pop_empty_list() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-pop_empty_list"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with(RuntimeError::EmptyList.to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn err_push_non_list() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-push_non_list"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::ExpectedList(Value::Num(1)).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_concat_arg_mismatch() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-concat_arg_mismatch"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::ArgMismatch("concat".to_string(), 2, 1).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn list_equality() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("list_equality"));
+    cmd.assert()
+        .success()
+        .stdout("true false false true true false true false true");
+
+    Ok(())
+}
@@ -1,11 +1,11 @@
-use std::process::Command;
+use std::{process::Command, time::Duration};
 
 use assert_cmd::prelude::*;
 use linger::error::{ParseError, RuntimeError};
 use predicates::{prelude::predicate::str::contains, str::starts_with};
 
 fn file_name_to_path(s: &str) -> String {
-    return format!("test_programs/loops/{}.ling", s);
+    format!("test_programs/loops/{}.ling", s)
 }
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
@@ -99,7 +99,7 @@ fn err_break_not_in_loop() -> TestResult {
     cmd.arg(file_name_to_path("err-break_not_in_loop"));
     cmd.assert()
         .failure()
-        .stderr(starts_with(RuntimeError::BreakNotInLoop.to_string()))
+        .stderr(starts_with(ParseError::BreakNotInLoop.to_string()))
         .stdout("");
 
     Ok(())
@@ -112,7 +112,7 @@ fn err_continue_not_in_loop() -> TestResult {
     cmd.arg(file_name_to_path("err-continue_not_in_loop"));
     cmd.assert()
         .failure()
-        .stderr(starts_with(RuntimeError::ContinueNotInLoop.to_string()))
+        .stderr(starts_with(ParseError::ContinueNotInLoop.to_string()))
         .stdout("");
 
     Ok(())
@@ -145,3 +145,154 @@ fn err_expected_initial_assign_or_init() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn for_with_continue() -> TestResult {
+    let mut cmd = assert_cmd::Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("for_with_continue"));
+    cmd.timeout(Duration::from_secs(5));
+    cmd.assert().success().stdout(starts_with("5"));
+
+    Ok(())
+}
+
+#[test]
+fn while_truthiness() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("while_truthiness"));
+    cmd.assert().success().stdout("123  0");
+
+    Ok(())
+}
+
+#[test]
+fn for_desugared() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--dump-desugared");
+    cmd.arg(file_name_to_path("for_desugared"));
+    cmd.assert().success().stdout(
+        "proc main() {
+  {
+    let i = 0;
+    while ((i < 3)) {
+      {
+        print(i);
+      }
+      i++;
+    }
+  }
+}
+",
+    );
+
+    Ok(())
+}
+
+#[test]
+fn loop_else() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("loop_else"));
+    cmd.assert().success().stdout("found missing completed 3");
+
+    Ok(())
+}
+
+#[test]
+fn large_loop() -> TestResult {
+    let mut cmd = assert_cmd::Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("large_loop"));
+    cmd.timeout(Duration::from_secs(10));
+    cmd.assert().success().stdout("2499900000");
+
+    Ok(())
+}
+
+#[test]
+fn large_loop_large_scope() -> TestResult {
+    let mut cmd = assert_cmd::Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("large_loop_large_scope"));
+    cmd.timeout(Duration::from_secs(10));
+    cmd.assert().success().stdout("624975000 19999");
+
+    Ok(())
+}
+
+#[test]
+fn variable_heavy_loop() -> TestResult {
+    let mut cmd = assert_cmd::Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("variable_heavy_loop"));
+    cmd.timeout(Duration::from_secs(10));
+    cmd.assert().success().stdout("150000 50001 99980001");
+
+    Ok(())
+}
+
+#[test]
+fn for_variable_scope() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("for_variable_scope"));
+    cmd.assert()
+        .success()
+        .stdout("[0, 1, 2, 3, 4, 10, 9, 8] outer");
+
+    Ok(())
+}
+
+#[test]
+fn err_for_variable_out_of_scope() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-for_variable_out_of_scope"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            RuntimeError::UnknownVariable("i".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn err_break_after_output() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-break_after_output"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(ParseError::BreakNotInLoop.to_string()))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn err_continue_in_lambda_in_loop() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-continue_in_lambda_in_loop"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(ParseError::ContinueNotInLoop.to_string()))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn loop_control_nesting() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("loop_control_nesting"));
+    cmd.assert().success().stdout("1 1");
+
+    Ok(())
+}
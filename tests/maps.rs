@@ -0,0 +1,71 @@
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use linger::{error::RuntimeError, interpreter::Value};
+use predicates::prelude::predicate::str::starts_with;
+
+fn file_name_to_path(s: &str) -> String {
+    format!("test_programs/maps/{}.ling", s)
+}
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+#[test]
+fn map_builtins() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("map_builtins"));
+    cmd.assert()
+        .success()
+        .stdout("{b: 3, a: 1} [b, a] [3, 1] true false false 3 map");
+
+    Ok(())
+}
+
+#[test]
+fn err_key_not_found() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-key_not_found"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::KeyNotFound(Value::Str("z".to_string())).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_keys_non_map() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-keys_non_map"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::ExpectedMap(Value::List(vec![Value::Num(1), Value::Num(2)])).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_map_of_odd_args() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-map_of_odd_args"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::ArgMismatch("map_of".to_string(), 4, 3).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn map_equality() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("map_equality"));
+    cmd.assert()
+        .success()
+        .stdout("true false false true true false");
+
+    Ok(())
+}
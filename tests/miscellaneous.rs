@@ -5,10 +5,13 @@ use linger::{
     error::{ParseError, RuntimeError, TokenizerError},
     interpreter::Value,
 };
-use predicates::prelude::predicate::str::{contains, starts_with};
+use predicates::prelude::{
+    predicate::str::{contains, starts_with},
+    PredicateBooleanExt,
+};
 
 fn file_name_to_path(s: &str) -> String {
-    return format!("test_programs/miscellaneous/{}.ling", s);
+    format!("test_programs/miscellaneous/{}.ling", s)
 }
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
@@ -23,6 +26,74 @@ fn comments() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn block_comments() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("block_comments"));
+    cmd.assert()
+        .success()
+        .stdout(starts_with("1 // not a comment /* not a comment */  2"));
+
+    Ok(())
+}
+
+#[test]
+fn err_unterminated_comment() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-unterminated_comment"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(TokenizerError::UnterminatedComment.to_string()))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn number_literals() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("number_literals"));
+    cmd.assert()
+        .success()
+        .stdout(starts_with("255 31 10 0 256"));
+
+    Ok(())
+}
+
+#[test]
+fn err_invalid_number_literal() -> TestResult {
+    let mut cmd_hex = Command::cargo_bin("linger-core")?;
+
+    cmd_hex.arg(file_name_to_path("err-empty_hex_literal"));
+    cmd_hex.assert().failure().stdout("").stderr(starts_with(
+        TokenizerError::InvalidNumberLiteral("0x".to_string()).to_string(),
+    ));
+
+    let mut cmd_binary = Command::cargo_bin("linger-core")?;
+
+    cmd_binary.arg(file_name_to_path("err-invalid_binary_literal"));
+    cmd_binary.assert().failure().stdout("").stderr(starts_with(
+        TokenizerError::InvalidNumberLiteral("0b2".to_string()).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_integer_literal_overflow() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-integer_literal_overflow"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        TokenizerError::IntegerLiteralOverflow("9223372036854775808".to_string()).to_string(),
+    ));
+
+    Ok(())
+}
+
 #[test]
 fn string_indexing() -> TestResult {
     let mut cmd = Command::cargo_bin("linger-core")?;
@@ -147,3 +218,656 @@ fn err_unexpected_eof() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn read_line() -> TestResult {
+    let mut cmd = assert_cmd::Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("read_line"));
+    cmd.write_stdin("hello\nworld\n");
+    cmd.assert().success().stdout(starts_with("hello-world--"));
+
+    Ok(())
+}
+
+#[test]
+fn err_read_line_invalid_utf8() -> TestResult {
+    let mut cmd = assert_cmd::Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("read_line"));
+    cmd.write_stdin(vec![0xff, 0xfe, b'\n']);
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with("could not read a line from stdin: "));
+
+    Ok(())
+}
+
+#[test]
+fn number_conversion() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("number_conversion"));
+    cmd.assert()
+        .success()
+        .stdout(starts_with("true 5.0 -7 true 1.5! [1, 2]"));
+
+    Ok(())
+}
+
+#[test]
+fn err_to_num_malformed() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-to_num_malformed"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            RuntimeError::ParseFailure("12abc".to_string(), "a number").to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn to_bool() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("to_bool"));
+    cmd.assert()
+        .success()
+        .stdout("true false true false true false false true false false");
+
+    Ok(())
+}
+
+#[test]
+fn err_to_bool_malformed() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-to_bool_malformed"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            RuntimeError::ParseFailure("maybe".to_string(), "a bool").to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn typeof_builtin() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("typeof"));
+    cmd.assert()
+        .success()
+        .stdout(starts_with("num float true str lambda lambda list nil"));
+
+    Ok(())
+}
+
+#[test]
+fn err_typeof_builtin() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-typeof_builtin"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            RuntimeError::UnknownVariable("print".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn assert() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("assert"));
+    cmd.assert().success().stdout("nil");
+
+    Ok(())
+}
+
+#[test]
+fn err_assert() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-assert"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(RuntimeError::AssertionFailed(None).to_string()))
+        .stdout("");
+
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-assert_with_message"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with("assertion failed: one is not two"))
+        .stdout("");
+
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-assert_falsy"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(RuntimeError::AssertionFailed(None).to_string()))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn exit_code() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("exit_code"));
+    cmd.assert().code(3).stdout("");
+
+    let mut cmd_clamped = Command::cargo_bin("linger-core")?;
+
+    cmd_clamped.arg(file_name_to_path("exit_code_clamped"));
+    cmd_clamped.assert().code(255).stdout("");
+
+    let mut cmd_negative = Command::cargo_bin("linger-core")?;
+
+    cmd_negative.arg(file_name_to_path("exit_code_negative"));
+    cmd_negative.assert().code(1).stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn abs_min_max() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("abs_min_max"));
+    cmd.assert().success().stdout("3 4 1.5 2 5 -1 2.5 2.0");
+
+    Ok(())
+}
+
+#[test]
+fn err_min_non_number() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-min_non_number"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArg(Value::Str("2".to_string())).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn trace() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--trace");
+    cmd.arg(file_name_to_path("trace"));
+    cmd.assert()
+        .success()
+        .stdout("6")
+        .stderr(contains("[trace] call double(3)\n"))
+        .stderr(contains("[trace]   (2, 3) return (n * 2); => 6 (Return)\n"))
+        .stderr(contains("[trace] return double => 6\n"))
+        .stderr(contains(
+            "[trace] (5, 3) let x = double(3); => nil (Normal)\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn err_source_context() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-source_context"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(contains("3 |   print(a +);\n  |            ^\n"));
+
+    Ok(())
+}
+
+#[test]
+fn vars() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("vars"));
+    cmd.assert().success().stdout("[x, y, z]");
+
+    Ok(())
+}
+
+#[test]
+fn expr_mode() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--expr");
+    cmd.arg("print(1 + 2);");
+    cmd.assert().success().stdout("3");
+
+    Ok(())
+}
+
+#[test]
+fn err_expr_mode_unmatched_bracket() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--expr");
+    cmd.arg("print(1); }");
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with("unexpected token \"}\""));
+
+    Ok(())
+}
+
+#[test]
+fn time() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--time");
+    cmd.arg(file_name_to_path("trace"));
+    cmd.assert()
+        .success()
+        .stdout("6")
+        .stderr(contains("[time] tokenize: "))
+        .stderr(contains("[time] parse: "))
+        .stderr(contains("[time] desugar: "))
+        .stderr(contains("[time] check: "))
+        .stderr(contains("[time] interpret: "));
+
+    Ok(())
+}
+
+#[test]
+fn check() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--check");
+    cmd.arg(file_name_to_path("trace"));
+    cmd.assert().success().stdout("").stderr("");
+
+    Ok(())
+}
+
+#[test]
+fn err_check_parse_error() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--check");
+    cmd.arg(file_name_to_path("err-source_context"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with("unexpected token \")\""));
+
+    Ok(())
+}
+
+#[test]
+fn args() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("args"));
+    cmd.args(["hello", "big world"]);
+    cmd.assert()
+        .success()
+        .stdout("hello big world [hello, big world]");
+
+    Ok(())
+}
+
+#[test]
+fn args_after_program_are_not_flags() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("args"));
+    cmd.args(["--trace", "--int-width=32"]);
+    cmd.assert()
+        .success()
+        .stdout("--trace --int-width=32 [--trace, --int-width=32]")
+        .stderr("");
+
+    Ok(())
+}
+
+#[test]
+fn exit() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("exit"));
+    cmd.assert().code(2).stdout("0 0 0 1 0 2 1 0 1 1 ");
+
+    Ok(())
+}
+
+#[test]
+fn err_exit_code_out_of_range() -> TestResult {
+    for (file_name, code) in [("err-exit_negative", -1), ("err-exit_too_large", 256)] {
+        let mut cmd = Command::cargo_bin("linger-core")?;
+
+        cmd.arg(file_name_to_path(file_name));
+        cmd.assert().code(1).stdout("").stderr(starts_with(
+            RuntimeError::BadArg(Value::Num(code)).to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn floor_ceil_round() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    // round rounds halfway cases away from zero
+    cmd.arg(file_name_to_path("floor_ceil_round"));
+    cmd.assert().success().stdout("true true true -4 -3 -3 2 7");
+
+    Ok(())
+}
+
+#[test]
+fn err_round_overflow() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-round_overflow"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with(RuntimeError::IntegerOverflow.to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn upper_lower_trim() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("upper_lower_trim"));
+    cmd.assert().success().stdout("true true mixed 1 [a b]");
+
+    Ok(())
+}
+
+#[test]
+fn err_upper_non_string() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-upper_non_string"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with(RuntimeError::BadArg(Value::Num(1)).to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn contains_starts_with_ends_with() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("contains_starts_with_ends_with"));
+    cmd.assert()
+        .success()
+        .stdout("true false true true false false true false true");
+
+    Ok(())
+}
+
+#[test]
+fn err_contains_non_string() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-contains_non_string"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArg(Value::List(vec![Value::Str("a".to_string())])).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn clamp_sign() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("clamp_sign"));
+    cmd.assert()
+        .success()
+        .stdout("true true 2 2.5 1.5 true 0 1 -1 0 1");
+
+    Ok(())
+}
+
+#[test]
+fn err_clamp_reversed_bounds() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-clamp_reversed_bounds"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArgs(vec![Value::Num(3), Value::Num(0)]).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn sort() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("sort"));
+    cmd.assert().success().stdout(
+        "[1, 2, 3] [-1, 2, 2.5] [apple, fig, pear] [] [3, 2, 1] [[1, b], [1, d], [2, a], [2, c]]",
+    );
+
+    Ok(())
+}
+
+#[test]
+fn err_sort_mixed_list() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-sort_mixed_list"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArgs(vec![Value::Num(1), Value::Str("a".to_string())]).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn range() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("range"));
+    cmd.assert()
+        .success()
+        .stdout("[0, 1, 2] [1, 4, 7] [5, 3, 1] [] []");
+
+    Ok(())
+}
+
+#[test]
+fn err_range_wrong_signed_step() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-range_wrong_signed_step"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArg(Value::Num(-1)).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn main_params() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("main_params"));
+    cmd.args(["hello", "big world"]);
+    cmd.assert().success().stdout("hello 9 [hello, big world]");
+
+    Ok(())
+}
+
+#[test]
+fn err_main_too_many_params() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-main_too_many_params"));
+    cmd.args(["a", "b"]);
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::ArgMismatch("main".to_string(), 2, 1).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn now_sleep() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("now_sleep"));
+    cmd.assert().success().stdout("true nil nil true");
+
+    Ok(())
+}
+
+#[test]
+fn err_sleep_negative() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-sleep_negative"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArg(Value::Num(-1)).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_sleep_too_long() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    // the duration is computed the same way as in the program, so that it rounds the same way
+    let mut duration = 1.0;
+    for _ in 0..300 {
+        duration *= 10.0;
+    }
+    cmd.arg(file_name_to_path("err-sleep_too_long"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArg(Value::Float(duration)).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn warn_unused() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--warn-unused");
+    cmd.arg(file_name_to_path("unused_bindings"));
+    cmd.assert()
+        .success()
+        .stdout("1 2 5")
+        .stderr(starts_with("warning: unused variable x @ (6, 3)\n"))
+        .stderr(contains("warning: unused variable z2 @ (9, 3)\n"))
+        .stderr(contains("warning: unused variable b @ (11, 20)\n"))
+        .stderr(contains("unused variable y").not())
+        .stderr(contains("unused variable z ").not())
+        .stderr(contains("unused variable n ").not())
+        .stderr(contains("unused variable a ").not());
+
+    Ok(())
+}
+
+#[test]
+fn unused_without_warn_unused() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("unused_bindings"));
+    cmd.assert().success().stdout("1 2 5").stderr("");
+
+    Ok(())
+}
+
+#[test]
+fn format() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("format"));
+    cmd.assert()
+        .success()
+        .stdout("1 + 2 = 3 list: [1.5, true], nil! true");
+
+    Ok(())
+}
+
+#[test]
+fn err_format_extra_arg() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-format_extra_arg"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::ArgMismatch("format".to_string(), 3, 4).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn print_sep() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("print_sep"));
+    cmd.assert().success().stdout("1, 2, 3 | a | nil | [1, 2]");
+
+    Ok(())
+}
+
+#[test]
+fn err_print_sep_non_string_separator() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-print_sep_non_string_separator"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with(RuntimeError::BadArg(Value::Num(1)).to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn builtin_arity() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("builtin_arity"));
+    cmd.assert().success().stdout("a b [] nil");
+
+    Ok(())
+}
+
+#[test]
+fn err_len_no_args() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-len_no_args"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::ArgMismatch("len".to_string(), 1, 0).to_string(),
+    ));
+
+    Ok(())
+}
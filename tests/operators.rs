@@ -1,15 +1,16 @@
 use std::process::Command;
 
 use assert_cmd::prelude::*;
-use linger::error::RuntimeError;
+use linger::error::{ParseError, RuntimeError};
 use linger::interpreter::Value;
+use linger::tokenizer::Operator;
 use predicates::{
     prelude::{predicate::str::contains, PredicateBooleanExt},
     str::starts_with,
 };
 
 fn file_name_to_path(s: &str) -> String {
-    return format!("test_programs/operators/{}.ling", s);
+    format!("test_programs/operators/{}.ling", s)
 }
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
@@ -20,7 +21,7 @@ fn binary_operators() -> TestResult {
 
     cmd.arg(file_name_to_path("binary_operators"));
     cmd.assert().success().stdout(contains(
-        "12 \"hello world\" -2 true false true false true false false true false true true 5 0.5 35",
+        "12 \"hello world\" -2 true false true false true false false true false true true 5 0 35",
     ));
 
     Ok(())
@@ -55,7 +56,9 @@ fn assignment_operators() -> TestResult {
     let mut cmd = Command::cargo_bin("linger-core")?;
 
     cmd.arg(file_name_to_path("assignment_operators"));
-    cmd.assert().success().stdout(contains("15 -5"));
+    cmd.assert()
+        .success()
+        .stdout(contains("15 -5 7 28 3 1 49 1"));
 
     Ok(())
 }
@@ -67,7 +70,7 @@ fn operator_precedence() -> TestResult {
     cmd.arg(file_name_to_path("operator_precedence"));
     cmd.assert()
         .success()
-        .stdout(contains("23 1.5 false true true true"));
+        .stdout(contains("23 1 false true true true"));
 
     Ok(())
 }
@@ -82,6 +85,63 @@ fn short_circuiting() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn short_circuiting_unknown_var() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("short_circuiting_unknown_var"));
+    cmd.assert().success().stdout(contains("false true"));
+
+    Ok(())
+}
+
+#[test]
+fn logical_truthiness() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("logical_truthiness"));
+    cmd.assert()
+        .success()
+        .stdout("true true true true false false true false false true");
+
+    Ok(())
+}
+
+#[test]
+fn err_print_result_operand() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-print_result_operand"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(format!(
+            "{} @ (2, 3)",
+            RuntimeError::NilOperand(Operator::Plus)
+        )))
+        .stdout("a");
+
+    Ok(())
+}
+
+#[test]
+fn err_nil_operand() -> TestResult {
+    for (file_name, op) in [
+        ("err-nil_operand_negate", Operator::Minus),
+        ("err-nil_operand_relational", Operator::LT),
+        ("err-nil_operand_increment", Operator::PostIncrement),
+    ] {
+        let mut cmd = Command::cargo_bin("linger-core")?;
+
+        cmd.arg(file_name_to_path(file_name));
+        cmd.assert()
+            .failure()
+            .stdout("")
+            .stderr(starts_with(RuntimeError::NilOperand(op).to_string()));
+    }
+
+    Ok(())
+}
+
 #[test]
 fn err_bad_arg_plus_bool() -> TestResult {
     let mut cmd = Command::cargo_bin("linger-core")?;
@@ -111,3 +171,382 @@ fn err_assignment_operator_on_const() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn relational_operators() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("relational_operators"));
+    cmd.assert()
+        .success()
+        .stdout(contains("true true false false true true false"));
+
+    Ok(())
+}
+
+#[test]
+fn err_bad_arg_relational() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-bad_arg_relational"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            RuntimeError::BadArg(Value::Str("two".to_string())).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn equality_operators() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("equality_operators"));
+    cmd.assert()
+        .success()
+        .stdout(contains("true false true true true true"));
+
+    Ok(())
+}
+
+#[test]
+fn err_compare_procs() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-compare_procs"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with("bad args: [<lambda>, <lambda>]"))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn nested_unary_operators() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("nested_unary_operators"));
+    cmd.assert()
+        .success()
+        .stdout(contains("-5 false 5 true 5 true"));
+
+    Ok(())
+}
+
+#[test]
+fn err_negate_string() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-negate_string"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            RuntimeError::BadArg(Value::Str("x".to_string())).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn increment_result() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("increment_result"));
+    cmd.assert()
+        .success()
+        .stdout(contains("2 1").and(contains("1 1")));
+
+    Ok(())
+}
+
+#[test]
+fn float_arithmetic() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("float_arithmetic"));
+    cmd.assert()
+        .success()
+        .stdout(contains("4.5 2 3.5 3.0 0.75 1.5 true -2.5"));
+
+    Ok(())
+}
+
+#[test]
+fn err_division_by_zero() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-division_by_zero"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(RuntimeError::DivisionByZero.to_string()))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn exponentiation() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("exponentiation"));
+    cmd.assert()
+        .success()
+        .stdout("true 512 64 0.5 1.4142135623730951 12 4 1 -1 1 0 1");
+
+    Ok(())
+}
+
+#[test]
+fn err_integer_overflow() -> TestResult {
+    for file_name in [
+        "err-multiplication_overflow",
+        "err-exponentiation_overflow",
+        "err-increment_overflow",
+    ] {
+        let mut cmd = Command::cargo_bin("linger-core")?;
+
+        cmd.arg(file_name_to_path(file_name));
+        cmd.assert()
+            .failure()
+            .stdout("")
+            .stderr(starts_with(RuntimeError::IntegerOverflow.to_string()));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn bitwise_operators() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("bitwise"));
+    cmd.assert().success().stdout("2 16 4 7 -4 false true");
+
+    Ok(())
+}
+
+#[test]
+fn bitwise_precedence() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("bitwise_precedence"));
+    cmd.assert().success().stdout("true 6 7 4 4");
+
+    Ok(())
+}
+
+#[test]
+fn err_invalid_shift_amount() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-invalid_shift_amount"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::InvalidShiftAmount(64).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_bitwise_non_integer() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-bitwise_non_integer"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArg(Value::Float(1.5)).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn division() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("division"));
+    cmd.assert()
+        .success()
+        .stdout("true -3 true 3.5  true 3 -4 -4 -4.0");
+
+    Ok(())
+}
+
+#[test]
+fn err_floor_division_by_zero() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-floor_division_by_zero"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with(RuntimeError::DivisionByZero.to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn mixed_numeric_equality() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("mixed_numeric_equality"));
+    cmd.assert()
+        .success()
+        .stdout("true true false true true true");
+
+    Ok(())
+}
+
+#[test]
+fn sequence() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("sequence"));
+    cmd.assert().success().stdout("[1, 2] 2 2 2 [2, 1]");
+
+    Ok(())
+}
+
+#[test]
+fn sequence_starting_with_keyword() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("sequence_starting_with_keyword"));
+    cmd.assert().success().stdout("1 3 true");
+
+    Ok(())
+}
+
+#[test]
+fn logical_assignment_operators() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("logical_assignment_operators"));
+    cmd.assert()
+        .success()
+        .stdout("evaluatedevaluated false true false true");
+
+    Ok(())
+}
+
+#[test]
+fn logical_assignment_operators_result_in_bool() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("logical_assignment_bool_result"));
+    cmd.assert().success().stdout("true true false false bool");
+
+    Ok(())
+}
+
+#[test]
+fn chained_comparison() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("chained_comparison"));
+    cmd.assert()
+        .success()
+        .stdout("true false true true true falseevaluated  true false");
+
+    Ok(())
+}
+
+#[test]
+fn subtract_negated() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("subtract_negated"));
+    cmd.assert().success().stdout("5 5 0 3 5.0 -1");
+
+    Ok(())
+}
+
+#[test]
+fn subtract_negated_desugared() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--dump-desugared");
+    cmd.arg(file_name_to_path("subtract_negated"));
+    cmd.assert().success().stdout(
+        "proc main() {
+  let x = 4;
+  print((3 - (-2)), (3 - (-2)), (3 - --x), x, (3.5 - (-1.5)), (3 - ((-2) ** 2)));
+}
+",
+    );
+
+    Ok(())
+}
+
+#[test]
+fn err_unsupported_assignment_operator() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-unsupported_assignment_operator"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            ParseError::UnsupportedAssignOp(Operator::ShiftLeft).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn int_width_32_wraps() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--int-width=32");
+    cmd.arg(file_name_to_path("int_width"));
+    cmd.assert()
+        .success()
+        .stdout("-2 -2147483648 2147483647 0 -2147483648 -2147483648 -2147483648");
+
+    Ok(())
+}
+
+#[test]
+fn int_width_32_wraps_every_integer() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--int-width=32");
+    cmd.arg(file_name_to_path("int_width_sources"));
+    cmd.assert()
+        .success()
+        .stdout("-2147483648 -2147483648 true 1 2 1 -1139103743 0");
+
+    Ok(())
+}
+
+#[test]
+fn int_width_64_by_default() -> TestResult {
+    for args in [vec![], vec!["--int-width=64"]] {
+        let mut cmd = Command::cargo_bin("linger-core")?;
+
+        cmd.args(args);
+        cmd.arg(file_name_to_path("int_width"));
+        cmd.assert().success().stdout(
+            "4294967294 2147483648 -2147483649 4294967296 2147483648 2147483648 2147483648",
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn err_invalid_int_width() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--int-width=16");
+    cmd.arg(file_name_to_path("int_width"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr("invalid integer width 16: expected 32 or 64\n");
+
+    Ok(())
+}
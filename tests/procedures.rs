@@ -1,11 +1,15 @@
 use std::process::Command;
 
 use assert_cmd::prelude::*;
-use linger::error::{ParseError, RuntimeError};
+use linger::{
+    environment::DEFAULT_MAX_CALL_DEPTH,
+    error::{ParseError, RuntimeError},
+    tokenizer::{Token, TokenValue},
+};
 use predicates::prelude::predicate::str::contains;
 
 fn file_name_to_path(s: &str) -> String {
-    return format!("test_programs/procedures/{}.ling", s);
+    format!("test_programs/procedures/{}.ling", s)
 }
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
@@ -99,3 +103,343 @@ fn err_arg_mismatch() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn default_params() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("default_params"));
+    cmd.assert()
+        .success()
+        .stdout(contains("hello Sam hi Sam 6 1 11 3"));
+
+    Ok(())
+}
+
+#[test]
+fn err_required_param_after_default() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-required_param_after_default"));
+    cmd.assert()
+        .failure()
+        .stderr(contains(
+            ParseError::RequiredParamAfterDefault("name".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn err_missing_required_arg() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-missing_required_arg"));
+    cmd.assert()
+        .failure()
+        .stderr(contains(
+            RuntimeError::ArgMismatch("greet".to_string(), 1, 0).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn variadic_params() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("variadic_params"));
+    cmd.assert()
+        .success()
+        .stdout(contains("6 0 [a, :, []] [a, -, [1, 2]] [2]"));
+
+    Ok(())
+}
+
+#[test]
+fn err_variadic_missing_args() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-variadic_missing_args"));
+    cmd.assert()
+        .failure()
+        .stderr(contains(
+            RuntimeError::ArgMismatch("pair".to_string(), 2, 1).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn err_variadic_not_last() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-variadic_not_last"));
+    cmd.assert()
+        .failure()
+        .stderr(contains("unexpected token \",\""))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn err_undefined_procedure() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-undefined_procedure"));
+    cmd.assert()
+        .failure()
+        .stderr(contains(
+            ParseError::UndefinedProcedure("foo".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    let mut cmd_out_of_scope = Command::cargo_bin("linger-core")?;
+
+    cmd_out_of_scope.arg(file_name_to_path("err-procedure_out_of_scope"));
+    cmd_out_of_scope
+        .assert()
+        .failure()
+        .stderr(contains(
+            ParseError::UndefinedProcedure("f".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn err_infinite_recursion() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-infinite_recursion"));
+    cmd.assert().failure().stdout("").stderr(contains(
+        RuntimeError::StackOverflow(DEFAULT_MAX_CALL_DEPTH).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn mutual_recursion() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("mutual_recursion"));
+    cmd.assert().success().stdout("true false true");
+
+    Ok(())
+}
+
+#[test]
+fn err_lambda_arg_mismatch() -> TestResult {
+    let mut cmd_variable = Command::cargo_bin("linger-core")?;
+
+    cmd_variable.arg(file_name_to_path("err-lambda_variable_arg_mismatch"));
+    cmd_variable
+        .assert()
+        .failure()
+        .stderr(contains(
+            RuntimeError::ArgMismatch("f".to_string(), 2, 1).to_string(),
+        ))
+        .stdout("");
+
+    let mut cmd_anonymous = Command::cargo_bin("linger-core")?;
+
+    cmd_anonymous.arg(file_name_to_path("err-anonymous_lambda_arg_mismatch"));
+    cmd_anonymous
+        .assert()
+        .failure()
+        .stderr(contains(
+            RuntimeError::ArgMismatch("<lambda (a, ...rest)>".to_string(), 1, 0).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn immediately_invoked_lambda() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("immediately_invoked_lambda"));
+    cmd.assert().success().stdout("6 12 7");
+
+    Ok(())
+}
+
+#[test]
+fn err_immediately_invoked_lambda_arg_mismatch() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path(
+        "err-immediately_invoked_lambda_arg_mismatch",
+    ));
+    cmd.assert()
+        .failure()
+        .stderr(contains(
+            RuntimeError::ArgMismatch("<lambda (x, y)>".to_string(), 2, 1).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn trailing_commas() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("trailing_commas"));
+    cmd.assert().success().stdout("3 2 [1, 2]");
+
+    Ok(())
+}
+
+#[test]
+fn err_double_comma() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-double_comma"));
+    cmd.assert()
+        .failure()
+        .stderr(contains("unexpected token \",\""))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn spread_args() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("spread_args"));
+    cmd.assert().success().stdout("from 1 to 9 [0, 1, 2, 3] []");
+
+    Ok(())
+}
+
+#[test]
+fn err_spread_arg_mismatch() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-spread_arg_mismatch"));
+    cmd.assert().failure().stderr(contains(
+        RuntimeError::ArgMismatch("pair".to_string(), 2, 3).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_spread_into_builtin() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-spread_into_builtin"));
+    cmd.assert()
+        .failure()
+        .stderr(contains(
+            ParseError::SpreadIntoBuiltin("print".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn map_filter_reduce() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("map_filter_reduce"));
+    cmd.assert().success().stdout("[2, 4, 6] [2, 4] 6 ab []");
+
+    Ok(())
+}
+
+#[test]
+fn err_map_callback_arg_mismatch() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-map_callback_arg_mismatch"));
+    cmd.assert().failure().stderr(contains(
+        RuntimeError::ArgMismatch("<lambda (a, b)>".to_string(), 2, 1).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn top_level_proc_as_value() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("top_level_proc_as_value"));
+    cmd.assert().success().stdout("2 3 120 50 [2, 3]");
+
+    Ok(())
+}
+
+#[test]
+fn block_value_without_semicolon() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("block_value_without_semicolon"));
+    cmd.assert().success().stdout("2 7 yes no st");
+
+    Ok(())
+}
+
+#[test]
+fn err_missing_semicolon_before_statement() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-missing_semicolon_before_statement"));
+    cmd.assert().failure().stdout("").stderr(contains(
+        ParseError::Expected(
+            TokenValue::SEMICOLON,
+            Token(TokenValue::ID("print".to_string()), 4, 3),
+        )
+        .to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn warn_missing_return() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg("--warn-missing-return");
+    cmd.arg(file_name_to_path("missing_return"));
+    let warning = |proc_name: &str| {
+        format!(
+            "warning: {}\n",
+            ParseError::MissingReturn(proc_name.to_string())
+        )
+    };
+    cmd.assert()
+        .success()
+        .stdout("effect nil positive 1 nil 2 4 first")
+        .stderr(format!(
+            "{}{}{}",
+            warning("signOf"),
+            warning("breaksOut"),
+            warning("firstEven")
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn missing_return_without_warn_missing_return() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("missing_return"));
+    cmd.assert()
+        .success()
+        .stdout("effect nil positive 1 nil 2 4 first")
+        .stderr("");
+
+    Ok(())
+}
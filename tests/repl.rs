@@ -0,0 +1,65 @@
+use assert_cmd::Command;
+use linger::error::RuntimeError;
+use predicates::prelude::predicate::str::contains;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+#[test]
+fn repl_persists_definitions() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.write_stdin("let x = 1;\nx + 2\nproc double(n) { return n * 2; }\ndouble(x + 4)\n");
+    cmd.assert().success().stdout(contains("> > 3\n> > 10\n"));
+
+    Ok(())
+}
+
+#[test]
+fn repl_continues_after_error() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.write_stdin("let x = 1;\ny\nx\n");
+    cmd.assert()
+        .success()
+        .stdout(contains("> > > 1\n"))
+        .stderr(contains(
+            RuntimeError::UnknownVariable("y".to_string()).to_string(),
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn repl_leaves_block_scope_after_error() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.write_stdin("let x = 1;\nif (true) { let x = 2; y; }\nx\n");
+    cmd.assert()
+        .success()
+        .stdout(contains("> > > 1\n"))
+        .stderr(contains(
+            RuntimeError::UnknownVariable("y".to_string()).to_string(),
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn repl_persists_destructured_variables() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.write_stdin("let [a, b] = [1, 2];\nlet [c] = [3];\na + b + c\n");
+    cmd.assert().success().stdout(contains("> > > 6\n"));
+
+    Ok(())
+}
+
+#[test]
+fn repl_exit_ends_session() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.write_stdin("print(1);\nexit(0);\nprint(2);\n");
+    cmd.assert().success().stdout("> 1> \n").stderr("");
+
+    Ok(())
+}
@@ -1,11 +1,11 @@
 use std::process::Command;
 
 use assert_cmd::prelude::*;
-use linger::error::RuntimeError;
+use linger::error::{ParseError, RuntimeError};
 use predicates::prelude::predicate::str::contains;
 
 fn file_name_to_path(s: &str) -> String {
-    return format!("test_programs/scope/{}.ling", s);
+    format!("test_programs/scope/{}.ling", s)
 }
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
@@ -79,3 +79,119 @@ fn err_unknown_var() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn err_const_redeclaration() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-const_redeclaration"));
+    cmd.assert()
+        .failure()
+        .stderr(contains(
+            RuntimeError::RedeclareConstant("num".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn shadowing_reassignment() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("shadowing_reassignment"));
+    cmd.assert().success().stdout(contains("1 1"));
+
+    Ok(())
+}
+
+#[test]
+fn err_if_block_var_scope() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-if_block_var_scope"));
+    cmd.assert()
+        .failure()
+        .stderr(contains(
+            RuntimeError::UnknownVariable("a".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn err_unknown_var_position() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-unknown_var_position"));
+    cmd.assert()
+        .failure()
+        .stderr(contains(
+            RuntimeError::At(
+                Box::new(RuntimeError::UnknownVariable("a".to_string())),
+                (3, 3),
+            )
+            .to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn err_caller_locals_not_visible() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-caller_locals_not_visible"));
+    cmd.assert()
+        .failure()
+        .stderr(contains(
+            RuntimeError::UnknownVariable("secret".to_string()).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn block_expression() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("block_expression"));
+    cmd.assert().success().stdout("10 2 4 1 2 30 6 big 5 ab");
+
+    Ok(())
+}
+
+#[test]
+fn err_block_expression_scope() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-block_expression_scope"));
+    cmd.assert()
+        .failure()
+        .stderr(contains(
+            RuntimeError::At(
+                Box::new(RuntimeError::UnknownVariable("x".to_string())),
+                (3, 3),
+            )
+            .to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn err_break_in_block_expression() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-break_in_block_expression"));
+    cmd.assert()
+        .failure()
+        .stderr(contains(ParseError::BreakNotInLoop.to_string()))
+        .stdout("");
+
+    Ok(())
+}
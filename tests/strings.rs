@@ -0,0 +1,334 @@
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use linger::{
+    error::{RuntimeError, TokenizerError},
+    interpreter::Value,
+};
+use predicates::prelude::predicate::str::{contains, starts_with};
+
+fn file_name_to_path(s: &str) -> String {
+    format!("test_programs/strings/{}.ling", s)
+}
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+#[test]
+fn len() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("len"));
+    cmd.assert().success().stdout(contains("5 0 5"));
+
+    Ok(())
+}
+
+#[test]
+fn err_len_non_string() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-len_non_string"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(RuntimeError::BadArg(Value::Num(5)).to_string()))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn err_len_arg_mismatch() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-len_arg_mismatch"));
+    cmd.assert()
+        .failure()
+        .stderr(starts_with(
+            RuntimeError::ArgMismatch("len".to_string(), 1, 2).to_string(),
+        ))
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn escape_sequences() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("escape_sequences"));
+    cmd.assert()
+        .success()
+        .stdout(starts_with("a\tb \u{1F600} HI 1"));
+
+    Ok(())
+}
+
+#[test]
+fn err_unicode_escape_out_of_range() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-unicode_escape_out_of_range"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        TokenizerError::InvalidUnicodeEscape("110000".to_string()).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_unicode_escape_non_hex() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-unicode_escape_non_hex"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        TokenizerError::InvalidUnicodeEscape("12G4".to_string()).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_unicode_escape_signed() -> TestResult {
+    for file_name in [
+        "err-unicode_escape_signed",
+        "err-unicode_escape_signed_template",
+    ] {
+        let mut cmd = Command::cargo_bin("linger-core")?;
+
+        cmd.arg(file_name_to_path(file_name));
+        cmd.assert().failure().stdout("").stderr(starts_with(
+            TokenizerError::InvalidUnicodeEscape("+41".to_string()).to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn err_unicode_escape_too_many_digits() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-unicode_escape_too_many_digits"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        TokenizerError::InvalidUnicodeEscape("0000041".to_string()).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn repetition() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("repetition"));
+    cmd.assert().success().stdout(starts_with("----- true []"));
+
+    Ok(())
+}
+
+#[test]
+fn err_repeat_by_string() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-repeat_by_string"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArgs(vec![
+            Value::Str("x".to_string()),
+            Value::Str("y".to_string()),
+        ])
+        .to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_repeat_negative_count() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-repeat_negative_count"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArg(Value::Num(-1)).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn indexing() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("indexing"));
+    cmd.assert().success().stdout("a e é o");
+
+    Ok(())
+}
+
+#[test]
+fn err_index_out_of_bounds() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-index_out_of_bounds"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with(RuntimeError::IndexOutOfBounds(3).to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn substr() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("substr"));
+    cmd.assert().success().stdout("true lo hé  0 0");
+
+    Ok(())
+}
+
+#[test]
+fn err_substr_start_out_of_bounds() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-substr_start_out_of_bounds"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with(RuntimeError::IndexOutOfBounds(6).to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn err_substr_negative_length() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-substr_negative_length"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArg(Value::Num(-1)).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn split_join() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("split_join"));
+    cmd.assert().success().stdout("[a, b, c] c a,b,c a-b-c ");
+
+    Ok(())
+}
+
+#[test]
+fn err_join_non_string() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-join_non_string"));
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(starts_with(RuntimeError::BadArg(Value::Num(2)).to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn template_literal() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("template_literal"));
+    cmd.assert()
+        .success()
+        .stdout("hello Ada, you are 36 years old true true ${name} `ADA`");
+
+    Ok(())
+}
+
+#[test]
+fn err_unterminated_template_literal() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-unterminated_template_literal"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        TokenizerError::UnterminatedTemplateLiteral.to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_unterminated_template_expression() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-unterminated_template_expression"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        TokenizerError::UnterminatedTemplateExpression.to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn chr_ord() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("chr_ord"));
+    cmd.assert()
+        .success()
+        .stdout("A true 65 true true char true λ 955 true [a, b, c]");
+
+    Ok(())
+}
+
+#[test]
+fn char_equals_single_char_string() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("char_equals_single_char_string"));
+    cmd.assert()
+        .success()
+        .stdout("true true true false false true");
+
+    Ok(())
+}
+
+#[test]
+fn err_chr_invalid_codepoint() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-chr_invalid_codepoint"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArg(Value::Num(55296)).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn err_ord_multiple_chars() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("err-ord_multiple_chars"));
+    cmd.assert().failure().stdout("").stderr(starts_with(
+        RuntimeError::BadArg(Value::Str("ab".to_string())).to_string(),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn repr() -> TestResult {
+    let mut cmd = Command::cargo_bin("linger-core")?;
+
+    cmd.arg(file_name_to_path("repr"));
+    cmd.assert().success().stdout(concat!(
+        r#""a\nb" "tab\there \"quoted\" \\ \u{7}" 1 chr(65) "#,
+        r#"["x", nil, [2.0]] map_of("k", "v") "#,
+        "a\tb",
+    ));
+
+    Ok(())
+}
@@ -4,7 +4,7 @@ use assert_cmd::prelude::*;
 use predicates::prelude::predicate::str::{contains, starts_with};
 
 fn file_name_to_path(s: &str) -> String {
-    return format!("test_programs/trivial_programs/{}.ling", s);
+    format!("test_programs/trivial_programs/{}.ling", s)
 }
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;